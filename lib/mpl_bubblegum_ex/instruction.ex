@@ -0,0 +1,34 @@
+defmodule MplBubblegumEx.AccountMeta do
+  @moduledoc """
+  Represents an account used by an instruction.
+  """
+  defstruct [
+    :pubkey,       # Base58 encoded Solana public key
+    :is_signer,    # Boolean - whether the account must sign the transaction
+    :is_writable   # Boolean - whether the instruction writes to the account
+  ]
+
+  @type t :: %__MODULE__{
+    pubkey: String.t(),
+    is_signer: boolean(),
+    is_writable: boolean()
+  }
+end
+
+defmodule MplBubblegumEx.Instruction do
+  @moduledoc """
+  Represents a Solana instruction, for composing Bubblegum instructions with
+  other instructions in a single transaction.
+  """
+  defstruct [
+    :program_id,   # Base58 encoded program ID
+    :accounts,     # List of %AccountMeta{} in instruction order
+    :data          # Binary - serialized instruction data
+  ]
+
+  @type t :: %__MODULE__{
+    program_id: String.t(),
+    accounts: [MplBubblegumEx.AccountMeta.t()],
+    data: binary()
+  }
+end
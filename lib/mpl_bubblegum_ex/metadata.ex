@@ -137,6 +137,32 @@ defmodule MplBubblegumEx.MetadataArgs do
   end
 end
 
+defmodule MplBubblegumEx.UpdateArgs do
+  @moduledoc """
+  Represents an update to the metadata of a compressed NFT. Fields left as
+  `nil` keep their current value.
+  """
+  defstruct [
+    :name,                    # New name of the NFT
+    :symbol,                  # New symbol/ticker for the NFT
+    :uri,                     # New URI to the JSON metadata
+    :creators,                # New list of creators
+    :seller_fee_basis_points, # New royalty fee in basis points
+    :primary_sale_happened,   # Whether the primary sale has happened
+    :is_mutable               # Whether the NFT can be updated again
+  ]
+
+  @type t :: %__MODULE__{
+    name: String.t() | nil,
+    symbol: String.t() | nil,
+    uri: String.t() | nil,
+    creators: [MplBubblegumEx.Creator.t()] | nil,
+    seller_fee_basis_points: integer() | nil,
+    primary_sale_happened: boolean() | nil,
+    is_mutable: boolean() | nil
+  }
+end
+
 defmodule MplBubblegumEx.MintOptions do
   @moduledoc """
   Options for minting NFTs.
@@ -155,3 +181,72 @@ defmodule MplBubblegumEx.MintOptions do
     collection_authority_record_pda: String.t() | nil
   }
 end
+
+defmodule MplBubblegumEx.TreeOptions do
+  @moduledoc """
+  Options for creating merkle trees.
+  """
+  defstruct [
+    :public,              # Boolean - whether anyone can mint to the tree
+    :tree_creator,        # Binary keypair or base58 pubkey of the tree creator, defaults to the payer
+    :log_wrapper,         # Base58 pubkey of the log wrapper (noop) program
+    :compression_program, # Base58 pubkey of the account compression program
+    :system_program       # Base58 pubkey of the system program
+  ]
+
+  @type t :: %__MODULE__{
+    public: boolean() | nil,
+    tree_creator: binary() | nil,
+    log_wrapper: String.t() | nil,
+    compression_program: String.t() | nil,
+    system_program: String.t() | nil
+  }
+end
+
+defmodule MplBubblegumEx.SendOptions do
+  @moduledoc """
+  Options for functions that send a transaction.
+
+  `compute_unit_limit` is either a number of compute units or `:auto`, which
+  simulates the transaction first and sets the limit to the consumed units
+  plus `compute_unit_margin` percent (default: 10). When `nil`, no compute
+  budget instructions are added and the runtime defaults apply.
+
+  `commitment` is the level the transaction must reach before the call
+  returns, and `preflight_commitment` the level used for the preflight
+  simulation. Both default to the commitment of the RPC client.
+  `max_retries` is how often the RPC node rebroadcasts the transaction
+  (default: until the blockhash expires).
+
+  Until the transaction is seen by the cluster, it is also rebroadcast every
+  `rebroadcast_interval_ms` (default: 2000) until its blockhash expires. An
+  expired transaction is signed again with a fresh blockhash up to
+  `max_resign_attempts` times (default: 0), waiting `resign_backoff_ms`
+  (default: 500) before the first attempt and twice as long before each next.
+  """
+  defstruct [
+    :compute_unit_limit,                # Integer or :auto - compute unit limit of the transaction
+    :compute_unit_price_micro_lamports, # Integer - priority fee per compute unit, in micro-lamports
+    :compute_unit_margin,               # Integer - extra units in percent of the simulated usage
+    :commitment,                        # :processed, :confirmed or :finalized
+    :skip_preflight,                    # Boolean - send without the preflight simulation
+    :preflight_commitment,              # :processed, :confirmed or :finalized
+    :max_retries,                       # Integer - rebroadcasts done by the RPC node
+    :rebroadcast_interval_ms,           # Integer - delay between rebroadcasts of the same transaction
+    :max_resign_attempts,               # Integer - re-signs with a fresh blockhash after expiry
+    :resign_backoff_ms                  # Integer - delay before the first re-sign
+  ]
+
+  @type t :: %__MODULE__{
+    compute_unit_limit: non_neg_integer() | :auto | nil,
+    compute_unit_price_micro_lamports: non_neg_integer() | nil,
+    compute_unit_margin: non_neg_integer() | nil,
+    commitment: :processed | :confirmed | :finalized | nil,
+    skip_preflight: boolean() | nil,
+    preflight_commitment: :processed | :confirmed | :finalized | nil,
+    max_retries: non_neg_integer() | nil,
+    rebroadcast_interval_ms: non_neg_integer() | nil,
+    max_resign_attempts: non_neg_integer() | nil,
+    resign_backoff_ms: non_neg_integer() | nil
+  }
+end
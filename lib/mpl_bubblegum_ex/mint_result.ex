@@ -0,0 +1,39 @@
+defmodule MplBubblegumEx.MintResult do
+  @moduledoc """
+  A confirmed mint with the leaf it appended to the tree, read from the
+  LeafSchema event Bubblegum logged in the transaction. `signature`, `slot`
+  and `commitment` are the same as in `MplBubblegumEx.SendResult`.
+
+  The hashes, nonce and leaf index are what transfer and the other leaf
+  instructions take, next to a proof of the leaf.
+
+  The event is waited for up to the client's `confirm_timeout_ms`, since the
+  node only returns confirmed transactions; without a confirm timeout it is
+  requested once. A mint that landed is always returned as a `MintResult`.
+  When the event cannot be read, `asset_id`, `leaf_index` and `nonce` come
+  from the tree's mint count before and after the mint, and the hashes are
+  `nil`. When another mint to the same tree landed in between, every leaf
+  field is `nil`.
+  """
+  defstruct [
+    :signature,   # String - base58 transaction signature
+    :slot,        # Integer - slot the transaction was processed in
+    :commitment,  # :processed, :confirmed or :finalized
+    :asset_id,    # String - base58 asset id of the minted NFT
+    :leaf_index,  # Integer - index of the leaf in the merkle tree
+    :nonce,       # Integer - number of NFTs minted in the tree before this one
+    :data_hash,   # String - base58 hash of the metadata
+    :creator_hash # String - base58 hash of the creators
+  ]
+
+  @type t :: %__MODULE__{
+    signature: String.t(),
+    slot: non_neg_integer(),
+    commitment: :processed | :confirmed | :finalized,
+    asset_id: String.t() | nil,
+    leaf_index: non_neg_integer() | nil,
+    nonce: non_neg_integer() | nil,
+    data_hash: String.t() | nil,
+    creator_hash: String.t() | nil
+  }
+end
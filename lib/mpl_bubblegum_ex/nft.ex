@@ -5,6 +5,9 @@ defmodule MplBubblegumEx.NFT do
 
   alias MplBubblegumEx.Native
   alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.UpdateArgs
+  alias MplBubblegumEx.SendOptions
+  alias MplBubblegumEx.RpcClient
 
   @doc """
   Mints a new compressed NFT and confirms the transaction on Solana.
@@ -14,10 +17,12 @@ defmodule MplBubblegumEx.NFT do
     - `leaf_owner_pubkey`: NFT owner pubkey (string)
     - `payer_keypair`: 64-byte keypair (binary)
     - `metadata`: a %MetadataArgs{} struct
-    - `rpc_url`: Solana RPC URL
+    - `tree_authority_keypair` (optional): 64-byte keypair of the tree creator or tree delegate, defaults to the payer
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
 
   ## Returns
-    - `{:ok, signature}` on success
+    - `{:ok, %MintResult{}}` with the send result and the asset id and leaf of the minted NFT on success
     - `{:error, reason}` on failure
   """
   def mint(
@@ -25,19 +30,25 @@ defmodule MplBubblegumEx.NFT do
     leaf_owner_pubkey,
     payer_keypair,
     %MetadataArgs{} = metadata,
-    rpc_url
+    tree_authority_keypair \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
   ) do
     with {:ok, _} <- validate_keypair(tree_keypair),
          {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
          {:ok, _} <- validate_keypair(payer_keypair),
-         {:ok, _} <- MetadataArgs.validate(metadata) do
+         {:ok, _} <- validate_optional_keypair(tree_authority_keypair),
+         {:ok, _} <- MetadataArgs.validate(metadata),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
       # This already handles submission AND confirmation
       Native.mint_compressed_nft_tx(
         tree_keypair,
         leaf_owner_pubkey,
         payer_keypair,
+        tree_authority_keypair,
         metadata,
-        rpc_url
+        send_options,
+        client
       )
     else
       error -> error
@@ -55,10 +66,12 @@ defmodule MplBubblegumEx.NFT do
     - `collection_mint`: Collection mint pubkey (string)
     - `collection_authority`: 64-byte keypair for collection authority (binary)
     - `collection_authority_record_pda` (optional): PDA string (if required)
-    - `rpc_url`: Solana RPC URL
+    - `tree_authority_keypair` (optional): 64-byte keypair of the tree creator or tree delegate, defaults to the payer
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
 
   ## Returns
-    - `{:ok, signature}` on success
+    - `{:ok, %MintResult{}}` with the send result and the asset id and leaf of the minted NFT on success
     - `{:error, reason}` on failure
   """
   def mint_to_collection(
@@ -69,24 +82,30 @@ defmodule MplBubblegumEx.NFT do
     collection_mint,
     collection_authority,
     collection_authority_record_pda \\ nil,
-    rpc_url
+    tree_authority_keypair \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
   ) do
     with {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
          {:ok, _} <- validate_pubkey(collection_mint),
          {:ok, _} <- validate_keypair(payer_keypair),
+         {:ok, _} <- validate_optional_keypair(tree_authority_keypair),
          {:ok, _} <- validate_keypair(collection_authority),
          {:ok, _} <- validate_optional_pubkey(collection_authority_record_pda),
-         {:ok, _} <- MetadataArgs.validate(metadata) do
+         {:ok, _} <- MetadataArgs.validate(metadata),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
       # This already handles submission AND confirmation
       Native.mint_to_collection_tx(
         tree_keypair,
         leaf_owner_pubkey,
         payer_keypair,
+        tree_authority_keypair,
         metadata,
         collection_mint,
         collection_authority,
         collection_authority_record_pda,
-        rpc_url
+        send_options,
+        client
       )
     else
       error -> error
@@ -97,65 +116,1207 @@ defmodule MplBubblegumEx.NFT do
   Transfers a compressed NFT to a new owner.
 
   ## Parameters
-    - `tree_keypair`: Keypair of the merkle tree (binary format)
-    - `leaf_owner_keypair`: Keypair of the current owner (binary format)
+    - `tree`: Public key of the merkle tree (string), or its keypair (binary format)
+    - `leaf_owner_pubkey`: Public key of the current owner (string)
+    - `signer_keypair`: Keypair of the current owner or delegate (binary format)
     - `new_leaf_owner_pubkey`: Public key of the new owner (string)
     - `asset_id`: Asset ID of the NFT (string)
-    - `options`: Optional parameters (root, data_hash, creator_hash, nonce, index)
-    - `rpc_url`: Solana RPC URL
+    - `options`: Optional parameters (leaf_delegate, root, data_hash, creator_hash, nonce, index, proof)
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  The `:leaf_delegate` option defaults to the owner. When `signer_keypair`
+  belongs to the delegate, the transfer is signed and paid by the delegate.
+
+  The `:proof` option is the list of proof node hashes from leaf to root
+  (base58 strings). Nodes already stored in the tree's canopy are dropped
+  before the instruction is built, so the full proof can always be passed.
+
+  `root`, `data_hash`, `creator_hash`, `nonce` and `index` are all required to
+  rebuild the leaf, `{:error, :missing_leaf_fields}` is returned otherwise.
+  `asset_id` must be the asset id of `nonce` in the tree, or
+  `{:error, :asset_id_mismatch}` is returned.
 
   ## Returns
-    - `{:ok, signature}` on success
+    - `{:ok, %SendResult{}}` with the signature, slot and commitment reached on success
     - `{:error, reason}` on failure
   """
   def transfer(
-    tree_keypair,
-    leaf_owner_keypair,
+    tree,
+    leaf_owner_pubkey,
+    signer_keypair,
     new_leaf_owner_pubkey,
     asset_id,
     options \\ %{},
-    rpc_url
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
   ) do
-    with {:ok, _} <- validate_keypair(tree_keypair),
-         {:ok, _} <- validate_keypair(leaf_owner_keypair),
+    leaf_delegate = Map.get(options, :leaf_delegate)
+
+    with {:ok, _} <- validate_keypair_or_pubkey(tree),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate),
+         {:ok, _} <- validate_keypair(signer_keypair),
          {:ok, _} <- validate_pubkey(new_leaf_owner_pubkey),
-         {:ok, _} <- validate_pubkey(asset_id) do
+         {:ok, _} <- validate_pubkey(asset_id),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
 
       root = Map.get(options, :root)
       data_hash = Map.get(options, :data_hash)
       creator_hash = Map.get(options, :creator_hash)
       nonce = Map.get(options, :nonce)
       index = Map.get(options, :index)
+      proof = Map.get(options, :proof, [])
 
       Native.transfer_compressed_nft(
+        tree,
+        leaf_owner_pubkey,
+        leaf_delegate,
+        signer_keypair,
+        new_leaf_owner_pubkey,
+        asset_id,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        proof,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Async variant of `mint/7`. Returns `{:ok, ref}` right away and submits the
+  transaction on a native thread pool, so many mints can be in flight at once.
+
+  Once the transaction confirms or fails, `{ref, result}` is sent to the
+  calling process, where `result` is what `mint/7` would have returned:
+
+      {:ok, ref} = NFT.mint_async(tree, owner, payer, metadata, rpc_client)
+
+      receive do
+        {^ref, {:ok, %MintResult{asset_id: asset_id}}} -> asset_id
+        {^ref, {:error, reason}} -> reason
+      end
+
+  Invalid arguments are returned as `{:error, reason}` without sending a message,
+  and so is `{:error, :worker_pool_unavailable}` when the work cannot be queued.
+  An unexpected crash of the native code is sent as `{ref, {:error, :panic}}`.
+  """
+  def mint_async(
+    tree_keypair,
+    leaf_owner_pubkey,
+    payer_keypair,
+    %MetadataArgs{} = metadata,
+    tree_authority_keypair \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_keypair(tree_keypair),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_keypair(payer_keypair),
+         {:ok, _} <- validate_optional_keypair(tree_authority_keypair),
+         {:ok, _} <- MetadataArgs.validate(metadata),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.mint_compressed_nft_tx_async(
         tree_keypair,
-        leaf_owner_keypair,
+        leaf_owner_pubkey,
+        payer_keypair,
+        tree_authority_keypair,
+        metadata,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Async variant of `mint_to_collection/10`. Returns `{:ok, ref}` and later sends
+  `{ref, result}` to the calling process, see `mint_async/7`.
+  """
+  def mint_to_collection_async(
+    tree_keypair,
+    leaf_owner_pubkey,
+    payer_keypair,
+    %MetadataArgs{} = metadata,
+    collection_mint,
+    collection_authority,
+    collection_authority_record_pda \\ nil,
+    tree_authority_keypair \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_pubkey(collection_mint),
+         {:ok, _} <- validate_keypair(payer_keypair),
+         {:ok, _} <- validate_optional_keypair(tree_authority_keypair),
+         {:ok, _} <- validate_keypair(collection_authority),
+         {:ok, _} <- validate_optional_pubkey(collection_authority_record_pda),
+         {:ok, _} <- MetadataArgs.validate(metadata),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.mint_to_collection_tx_async(
+        tree_keypair,
+        leaf_owner_pubkey,
+        payer_keypair,
+        tree_authority_keypair,
+        metadata,
+        collection_mint,
+        collection_authority,
+        collection_authority_record_pda,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Async variant of `transfer/8`. Returns `{:ok, ref}` and later sends
+  `{ref, result}` to the calling process, see `mint_async/7`.
+  """
+  def transfer_async(
+    tree,
+    leaf_owner_pubkey,
+    signer_keypair,
+    new_leaf_owner_pubkey,
+    asset_id,
+    options \\ %{},
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    leaf_delegate = Map.get(options, :leaf_delegate)
+
+    with {:ok, _} <- validate_keypair_or_pubkey(tree),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate),
+         {:ok, _} <- validate_keypair(signer_keypair),
+         {:ok, _} <- validate_pubkey(new_leaf_owner_pubkey),
+         {:ok, _} <- validate_pubkey(asset_id),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.transfer_compressed_nft_async(
+        tree,
+        leaf_owner_pubkey,
+        leaf_delegate,
+        signer_keypair,
         new_leaf_owner_pubkey,
         asset_id,
+        Map.get(options, :root),
+        Map.get(options, :data_hash),
+        Map.get(options, :creator_hash),
+        Map.get(options, :nonce),
+        Map.get(options, :index),
+        Map.get(options, :proof, []),
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Builds the mint transaction without sending it, for submission through
+  another pipeline or co-signing by a wallet.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_pubkey`: NFT owner pubkey (string)
+    - `payer_pubkey`: Public key of the fee payer (string)
+    - `metadata`: a %MetadataArgs{} struct
+    - `recent_blockhash`: Recent blockhash to build the transaction with (base58 string)
+    - `build_opts`: Optional map:
+      - `:tree_authority` - Public key of the tree creator or tree delegate, defaults to the payer
+      - `:signers` - Keypairs to sign with (default: []), missing signatures are left empty
+      - `:encoding` - `:base58` (default) or `:base64`
+
+  ## Returns
+    - `{:ok, serialized_tx}` on success
+    - `{:error, reason}` on failure
+  """
+  def build_mint(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_pubkey,
+    %MetadataArgs{} = metadata,
+    recent_blockhash,
+    build_opts \\ %{}
+  ) do
+    tree_authority = Map.get(build_opts, :tree_authority)
+    signers = Map.get(build_opts, :signers, [])
+    encoding = Map.get(build_opts, :encoding, :base58)
+
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_pubkey(payer_pubkey),
+         {:ok, _} <- validate_optional_pubkey(tree_authority),
+         {:ok, _} <- validate_keypairs(signers),
+         {:ok, _} <- validate_encoding(encoding),
+         {:ok, _} <- MetadataArgs.validate(metadata) do
+      Native.build_mint_compressed_nft_tx(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        payer_pubkey,
+        tree_authority,
+        metadata,
+        signers,
+        recent_blockhash,
+        encoding
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Builds the mint to collection transaction without sending it.
+
+  Takes the same parameters as `build_mint/6`, plus the `collection_mint` and
+  the `collection_authority_pubkey`. `build_opts` also accepts
+  `:collection_authority_record_pda`.
+  """
+  def build_mint_to_collection(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_pubkey,
+    %MetadataArgs{} = metadata,
+    collection_mint,
+    collection_authority_pubkey,
+    recent_blockhash,
+    build_opts \\ %{}
+  ) do
+    tree_authority = Map.get(build_opts, :tree_authority)
+    collection_authority_record_pda = Map.get(build_opts, :collection_authority_record_pda)
+    signers = Map.get(build_opts, :signers, [])
+    encoding = Map.get(build_opts, :encoding, :base58)
+
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_pubkey(payer_pubkey),
+         {:ok, _} <- validate_optional_pubkey(tree_authority),
+         {:ok, _} <- validate_pubkey(collection_mint),
+         {:ok, _} <- validate_pubkey(collection_authority_pubkey),
+         {:ok, _} <- validate_optional_pubkey(collection_authority_record_pda),
+         {:ok, _} <- validate_keypairs(signers),
+         {:ok, _} <- validate_encoding(encoding),
+         {:ok, _} <- MetadataArgs.validate(metadata) do
+      Native.build_mint_to_collection_tx(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        payer_pubkey,
+        tree_authority,
+        metadata,
+        collection_mint,
+        collection_authority_pubkey,
+        collection_authority_record_pda,
+        signers,
+        recent_blockhash,
+        encoding
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Builds the transfer transaction without sending it.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_pubkey`: Public key of the current owner (string)
+    - `signer_pubkey`: Public key of the owner or delegate authorizing the transfer, also the fee payer (string)
+    - `new_leaf_owner_pubkey`: Public key of the new owner (string)
+    - `leaf`: Map with the current leaf state (root, data_hash, creator_hash, nonce, index)
+    - `proof`: List of proof node hashes from leaf to root (base58 strings)
+    - `canopy_depth`: Canopy depth of the tree, used to trim the proof
+    - `recent_blockhash`: Recent blockhash to build the transaction with (base58 string)
+    - `build_opts`: Optional map:
+      - `:leaf_delegate` - Public key of the current delegate, defaults to the owner
+      - `:signers` - Keypairs to sign with (default: []), missing signatures are left empty
+      - `:encoding` - `:base58` (default) or `:base64`
+
+  ## Returns
+    - `{:ok, serialized_tx}` on success
+    - `{:error, reason}` on failure
+  """
+  def build_transfer(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    signer_pubkey,
+    new_leaf_owner_pubkey,
+    %{root: root, data_hash: data_hash, creator_hash: creator_hash, nonce: nonce, index: index},
+    proof,
+    canopy_depth,
+    recent_blockhash,
+    build_opts \\ %{}
+  ) do
+    leaf_delegate = Map.get(build_opts, :leaf_delegate)
+    signers = Map.get(build_opts, :signers, [])
+    encoding = Map.get(build_opts, :encoding, :base58)
+
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate),
+         {:ok, _} <- validate_pubkey(signer_pubkey),
+         {:ok, _} <- validate_pubkey(new_leaf_owner_pubkey),
+         {:ok, _} <- validate_keypairs(signers),
+         {:ok, _} <- validate_encoding(encoding) do
+      Native.build_transfer_compressed_nft(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        leaf_delegate,
+        signer_pubkey,
+        new_leaf_owner_pubkey,
         root,
         data_hash,
         creator_hash,
         nonce,
         index,
-        rpc_url
+        proof,
+        canopy_depth,
+        signers,
+        recent_blockhash,
+        encoding
       )
     else
       error -> error
     end
   end
 
-  defp validate_pubkey(pubkey) do
-    if Native.validate_pubkey_nif(pubkey),
-      do: {:ok, pubkey},
-      else: {:error, "Invalid pubkey: #{pubkey}"}
+  @doc """
+  Simulates the mint transaction instead of submitting it, e.g. to check a
+  mint before spending SOL on it. No keypairs are needed since signatures
+  are not verified during simulation.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_pubkey`: NFT owner pubkey (string)
+    - `payer_pubkey`: Public key of the fee payer (string)
+    - `metadata`: a %MetadataArgs{} struct
+    - `tree_authority_pubkey` (optional): Public key of the tree creator or tree delegate, defaults to the payer
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  ## Returns
+    - `{:ok, %SimulationResult{}}` when the simulation ran, even if the transaction would fail
+    - `{:error, reason}` on failure
+  """
+  def simulate_mint(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_pubkey,
+    %MetadataArgs{} = metadata,
+    tree_authority_pubkey \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_pubkey(payer_pubkey),
+         {:ok, _} <- validate_optional_pubkey(tree_authority_pubkey),
+         {:ok, _} <- MetadataArgs.validate(metadata),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.simulate_mint_compressed_nft_tx(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        payer_pubkey,
+        tree_authority_pubkey,
+        metadata,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
   end
 
-  defp validate_optional_pubkey(nil), do: {:ok, nil}
-  defp validate_optional_pubkey(pubkey), do: validate_pubkey(pubkey)
+  @doc """
+  Simulates the transfer transaction instead of submitting it.
 
-  defp validate_keypair(keypair) do
-    if byte_size(keypair) == 64,
-      do: {:ok, keypair},
-      else: {:error, "Keypair must be 64 bytes"}
+  Takes the same parameters as `transfer_instruction/8`, without the canopy
+  depth, which is read from the tree account. The proof can be passed in full.
+
+  ## Returns
+    - `{:ok, %SimulationResult{}}` when the simulation ran, even if the transaction would fail
+    - `{:error, reason}` on failure
+  """
+  def simulate_transfer(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    signer_pubkey,
+    new_leaf_owner_pubkey,
+    %{root: root, data_hash: data_hash, creator_hash: creator_hash, nonce: nonce, index: index},
+    proof,
+    leaf_delegate_pubkey \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate_pubkey),
+         {:ok, _} <- validate_pubkey(signer_pubkey),
+         {:ok, _} <- validate_pubkey(new_leaf_owner_pubkey),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.simulate_transfer_compressed_nft(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        leaf_delegate_pubkey,
+        signer_pubkey,
+        new_leaf_owner_pubkey,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        proof,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Returns the MintV1 instruction as an %Instruction{} struct, without building
+  a transaction.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_pubkey`: NFT owner pubkey (string)
+    - `payer_pubkey`: Public key of the fee payer (string)
+    - `metadata`: a %MetadataArgs{} struct
+    - `tree_authority_pubkey` (optional): Public key of the tree creator or tree delegate, defaults to the payer
+
+  ## Returns
+    - `{:ok, instruction}` on success
+    - `{:error, reason}` on failure
+  """
+  def mint_instruction(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_pubkey,
+    %MetadataArgs{} = metadata,
+    tree_authority_pubkey \\ nil
+  ) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_pubkey(payer_pubkey),
+         {:ok, _} <- validate_optional_pubkey(tree_authority_pubkey),
+         {:ok, _} <- MetadataArgs.validate(metadata) do
+      Native.mint_compressed_nft_ix(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        payer_pubkey,
+        tree_authority_pubkey,
+        metadata
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Returns the MintToCollectionV1 instruction as an %Instruction{} struct,
+  without building a transaction.
+
+  Takes the same parameters as `mint_instruction/5`, plus the
+  `collection_mint`, the `collection_authority_pubkey` and an optional
+  `collection_authority_record_pda`.
+  """
+  def mint_to_collection_instruction(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_pubkey,
+    %MetadataArgs{} = metadata,
+    collection_mint,
+    collection_authority_pubkey,
+    collection_authority_record_pda \\ nil,
+    tree_authority_pubkey \\ nil
+  ) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_pubkey(payer_pubkey),
+         {:ok, _} <- validate_optional_pubkey(tree_authority_pubkey),
+         {:ok, _} <- validate_pubkey(collection_mint),
+         {:ok, _} <- validate_pubkey(collection_authority_pubkey),
+         {:ok, _} <- validate_optional_pubkey(collection_authority_record_pda),
+         {:ok, _} <- MetadataArgs.validate(metadata) do
+      Native.mint_to_collection_ix(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        payer_pubkey,
+        tree_authority_pubkey,
+        metadata,
+        collection_mint,
+        collection_authority_pubkey,
+        collection_authority_record_pda
+      )
+    else
+      error -> error
+    end
   end
+
+  @doc """
+  Returns the transfer instruction as an %Instruction{} struct, without
+  building a transaction.
+
+  Takes the same parameters as `build_transfer/9`, minus the blockhash and
+  build options. The optional `leaf_delegate_pubkey` defaults to the owner.
+  """
+  def transfer_instruction(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    signer_pubkey,
+    new_leaf_owner_pubkey,
+    %{root: root, data_hash: data_hash, creator_hash: creator_hash, nonce: nonce, index: index},
+    proof,
+    canopy_depth,
+    leaf_delegate_pubkey \\ nil
+  ) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate_pubkey),
+         {:ok, _} <- validate_pubkey(signer_pubkey),
+         {:ok, _} <- validate_pubkey(new_leaf_owner_pubkey) do
+      Native.transfer_compressed_nft_ix(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        leaf_delegate_pubkey,
+        signer_pubkey,
+        new_leaf_owner_pubkey,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        proof,
+        canopy_depth
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Derives the asset id of the NFT minted with `nonce` in a tree, without
+  calling an RPC node. The nonce of a mint is the number of NFTs minted in the
+  tree before it, so the id can be known before the mint confirms.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `nonce`: Nonce of the leaf (non-negative integer)
+
+  ## Returns
+    - `{:ok, asset_id}` with the base58 asset id on success
+    - `{:error, reason}` on failure
+  """
+  def get_asset_id(tree_pubkey, nonce) when is_integer(nonce) and nonce >= 0 do
+    with {:ok, _} <- validate_pubkey(tree_pubkey) do
+      Native.get_asset_id(tree_pubkey, nonce)
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Checks an asset id against a range of nonces of a tree and returns the nonce
+  it was minted with. Every nonce in the range is derived in turn, so keep the
+  range to the nonces the asset can have. Ranges of more than 1_048_576 (2^20)
+  nonces are rejected with `{:error, :invalid_nonce_range}`.
+
+  ## Parameters
+    - `asset_id`: Asset ID of the NFT (string)
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `nonces`: Range of nonces to check, e.g. `0..99`
+
+  ## Returns
+    - `{:ok, nonce}` when the asset belongs to the tree at a nonce in the range
+    - `{:error, :not_found}` when it does not
+    - `{:error, :invalid_nonce_range}` when the range is empty or too large
+    - `{:error, reason}` on other failures
+  """
+  def find_asset_nonce(asset_id, tree_pubkey, first..last//1) when first >= 0 do
+    with {:ok, _} <- validate_pubkey(asset_id),
+         {:ok, _} <- validate_pubkey(tree_pubkey) do
+      Native.find_asset_nonce(asset_id, tree_pubkey, first, last)
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Burns a compressed NFT, removing its leaf from the merkle tree.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_pubkey`: Public key of the current owner (string)
+    - `signer_keypair`: Keypair of the owner or the delegate (binary format)
+    - `leaf`: Map with the current leaf state (root, data_hash, creator_hash, nonce, index)
+    - `proof`: List of proof node hashes from leaf to root (base58 strings), trimmed to the canopy
+    - `leaf_delegate_pubkey` (optional): Public key of the current delegate, defaults to the owner
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  ## Returns
+    - `{:ok, %SendResult{}}` with the signature, slot and commitment reached on success
+    - `{:error, reason}` on failure
+  """
+  def burn(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    signer_keypair,
+    %{root: root, data_hash: data_hash, creator_hash: creator_hash, nonce: nonce, index: index},
+    proof,
+    leaf_delegate_pubkey \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate_pubkey),
+         {:ok, _} <- validate_keypair(signer_keypair),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.burn_compressed_nft(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        leaf_delegate_pubkey,
+        signer_keypair,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        proof,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Sets a new delegate for a compressed NFT.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_keypair`: Keypair of the current owner (binary format)
+    - `new_leaf_delegate_pubkey`: Public key of the new delegate (string)
+    - `leaf`: Map with the current leaf state (root, data_hash, creator_hash, nonce, index)
+    - `proof`: List of proof node hashes from leaf to root (base58 strings), trimmed to the canopy
+    - `previous_leaf_delegate_pubkey` (optional): Public key of the current delegate, defaults to the owner
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  ## Returns
+    - `{:ok, %SendResult{}}` with the signature, slot and commitment reached on success
+    - `{:error, reason}` on failure
+  """
+  def delegate(
+    tree_pubkey,
+    leaf_owner_keypair,
+    new_leaf_delegate_pubkey,
+    leaf,
+    proof,
+    previous_leaf_delegate_pubkey \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(new_leaf_delegate_pubkey) do
+      do_delegate(tree_pubkey, leaf_owner_keypair, previous_leaf_delegate_pubkey, new_leaf_delegate_pubkey, leaf, proof, send_options, rpc_client)
+    end
+  end
+
+  @doc """
+  Revokes the current delegate of a compressed NFT, resetting it to the owner.
+
+  Takes the same parameters as `delegate/8`, without the new delegate.
+  """
+  def revoke_delegate(
+    tree_pubkey,
+    leaf_owner_keypair,
+    leaf,
+    proof,
+    previous_leaf_delegate_pubkey,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    do_delegate(tree_pubkey, leaf_owner_keypair, previous_leaf_delegate_pubkey, nil, leaf, proof, send_options, rpc_client)
+  end
+
+  defp do_delegate(
+    tree_pubkey,
+    leaf_owner_keypair,
+    previous_leaf_delegate_pubkey,
+    new_leaf_delegate_pubkey,
+    %{root: root, data_hash: data_hash, creator_hash: creator_hash, nonce: nonce, index: index},
+    proof,
+    send_options,
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_keypair(leaf_owner_keypair),
+         {:ok, _} <- validate_optional_pubkey(previous_leaf_delegate_pubkey),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.delegate_compressed_nft(
+        tree_pubkey,
+        leaf_owner_keypair,
+        previous_leaf_delegate_pubkey,
+        new_leaf_delegate_pubkey,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        proof,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Marks a creator of a compressed NFT as verified. Must be signed by that creator.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_pubkey`: Public key of the current owner (string)
+    - `creator_keypair`: Keypair of the creator to verify (binary format), also pays the fees
+    - `metadata`: the current %MetadataArgs{} of the NFT, with the creator still unverified
+    - `leaf`: Map with the current leaf state (root, nonce, index)
+    - `proof`: List of proof node hashes from leaf to root (base58 strings), trimmed to the canopy
+    - `leaf_delegate_pubkey` (optional): Public key of the current delegate, defaults to the owner
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  ## Returns
+    - `{:ok, %SendResult{}}` with the signature, slot and commitment reached on success
+    - `{:error, reason}` on failure
+  """
+  def verify_creator(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    creator_keypair,
+    %MetadataArgs{} = metadata,
+    leaf,
+    proof,
+    leaf_delegate_pubkey \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    update_creator_verification(:verify_creator, tree_pubkey, leaf_owner_pubkey, leaf_delegate_pubkey, creator_keypair, metadata, leaf, proof, send_options, rpc_client)
+  end
+
+  @doc """
+  Marks a verified creator of a compressed NFT as unverified. Must be signed by that creator.
+
+  Takes the same parameters as `verify_creator/9`, with `metadata` holding
+  the creator as currently verified.
+  """
+  def unverify_creator(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    creator_keypair,
+    %MetadataArgs{} = metadata,
+    leaf,
+    proof,
+    leaf_delegate_pubkey \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    update_creator_verification(:unverify_creator, tree_pubkey, leaf_owner_pubkey, leaf_delegate_pubkey, creator_keypair, metadata, leaf, proof, send_options, rpc_client)
+  end
+
+  defp update_creator_verification(
+    nif,
+    tree_pubkey,
+    leaf_owner_pubkey,
+    leaf_delegate_pubkey,
+    creator_keypair,
+    metadata,
+    %{root: root, nonce: nonce, index: index},
+    proof,
+    send_options,
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate_pubkey),
+         {:ok, _} <- validate_keypair(creator_keypair),
+         {:ok, _} <- MetadataArgs.validate(metadata),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      apply(Native, nif, [
+        tree_pubkey,
+        leaf_owner_pubkey,
+        leaf_delegate_pubkey,
+        creator_keypair,
+        metadata,
+        root,
+        nonce,
+        index,
+        proof,
+        send_options,
+        client
+      ])
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Verifies the collection of a compressed NFT. Must be signed by the collection authority.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_pubkey`: Public key of the current owner (string)
+    - `payer_keypair`: Keypair paying the fees (binary format)
+    - `metadata`: the current %MetadataArgs{} of the NFT, with the collection still unverified
+    - `collection_mint`: Public key of the collection mint (string)
+    - `collection_authority`: 64-byte keypair for collection authority (binary)
+    - `leaf`: Map with the current leaf state (root, nonce, index)
+    - `proof`: List of proof node hashes from leaf to root (base58 strings), trimmed to the canopy
+    - `options`: Optional parameters (leaf_delegate, tree_authority_keypair,
+      collection_authority_record_pda, collection_metadata, collection_edition)
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  `:collection_metadata` and `:collection_edition` default to the Token
+  Metadata accounts derived from `collection_mint`.
+
+  ## Returns
+    - `{:ok, %SendResult{}}` with the signature, slot and commitment reached on success
+    - `{:error, reason}` on failure
+  """
+  def verify_collection(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_keypair,
+    %MetadataArgs{} = metadata,
+    collection_mint,
+    collection_authority,
+    leaf,
+    proof,
+    options \\ %{},
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    update_collection(:verify_collection, tree_pubkey, leaf_owner_pubkey, payer_keypair, metadata, collection_mint, collection_authority, leaf, proof, options, send_options, rpc_client)
+  end
+
+  @doc """
+  Unverifies the collection of a compressed NFT. Must be signed by the collection authority.
+
+  Takes the same parameters as `verify_collection/11`, with `metadata` holding
+  the collection as currently verified.
+  """
+  def unverify_collection(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_keypair,
+    %MetadataArgs{} = metadata,
+    collection_mint,
+    collection_authority,
+    leaf,
+    proof,
+    options \\ %{},
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    update_collection(:unverify_collection, tree_pubkey, leaf_owner_pubkey, payer_keypair, metadata, collection_mint, collection_authority, leaf, proof, options, send_options, rpc_client)
+  end
+
+  @doc """
+  Sets the collection of a compressed NFT to `collection_mint` and verifies it.
+
+  Takes the same parameters as `verify_collection/11`, with `metadata` holding
+  the current collection (if any). Must also be signed by the tree creator or
+  tree delegate, given as the `:tree_authority_keypair` option (defaults to the payer).
+  """
+  def set_and_verify_collection(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_keypair,
+    %MetadataArgs{} = metadata,
+    collection_mint,
+    collection_authority,
+    leaf,
+    proof,
+    options \\ %{},
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    update_collection(:set_and_verify_collection, tree_pubkey, leaf_owner_pubkey, payer_keypair, metadata, collection_mint, collection_authority, leaf, proof, options, send_options, rpc_client)
+  end
+
+  defp update_collection(
+    nif,
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_keypair,
+    metadata,
+    collection_mint,
+    collection_authority,
+    %{root: root, nonce: nonce, index: index},
+    proof,
+    options,
+    send_options,
+    rpc_client
+  ) do
+    leaf_delegate_pubkey = Map.get(options, :leaf_delegate)
+    tree_authority_keypair = Map.get(options, :tree_authority_keypair)
+    collection_authority_record_pda = Map.get(options, :collection_authority_record_pda)
+    collection_metadata = Map.get(options, :collection_metadata)
+    collection_edition = Map.get(options, :collection_edition)
+
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate_pubkey),
+         {:ok, _} <- validate_keypair(payer_keypair),
+         {:ok, _} <- validate_optional_keypair(tree_authority_keypair),
+         {:ok, _} <- validate_pubkey(collection_mint),
+         {:ok, _} <- validate_keypair(collection_authority),
+         {:ok, _} <- validate_optional_pubkey(collection_authority_record_pda),
+         {:ok, _} <- validate_optional_pubkey(collection_metadata),
+         {:ok, _} <- validate_optional_pubkey(collection_edition),
+         {:ok, _} <- MetadataArgs.validate(metadata),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      apply(Native, nif, [
+        tree_pubkey,
+        leaf_owner_pubkey,
+        leaf_delegate_pubkey,
+        payer_keypair,
+        tree_authority_keypair,
+        metadata,
+        collection_mint,
+        collection_authority,
+        collection_authority_record_pda,
+        collection_metadata,
+        collection_edition,
+        root,
+        nonce,
+        index,
+        proof,
+        send_options,
+        client
+      ])
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Updates the metadata of a compressed NFT.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_pubkey`: Public key of the current owner (string)
+    - `payer_keypair`: Keypair paying the fees (binary format)
+    - `metadata`: the current %MetadataArgs{} of the NFT
+    - `update_args`: %UpdateArgs{} with the fields to change, `nil` fields are kept
+    - `leaf`: Map with the current leaf state (root, nonce, index)
+    - `proof`: List of proof node hashes from leaf to root (base58 strings), trimmed to the canopy
+    - `options`: Optional parameters (leaf_delegate, authority_keypair, collection_authority_record_pda)
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  The `:authority_keypair` option must be the collection authority when the
+  NFT is in a verified collection, and the tree creator or tree delegate
+  otherwise. It defaults to the payer.
+
+  ## Returns
+    - `{:ok, %SendResult{}}` with the signature, slot and commitment reached on success
+    - `{:error, reason}` on failure
+  """
+  def update_metadata(
+    tree_pubkey,
+    leaf_owner_pubkey,
+    payer_keypair,
+    %MetadataArgs{} = metadata,
+    %UpdateArgs{} = update_args,
+    %{root: root, nonce: nonce, index: index},
+    proof,
+    options \\ %{},
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    leaf_delegate_pubkey = Map.get(options, :leaf_delegate)
+    authority_keypair = Map.get(options, :authority_keypair)
+    collection_authority_record_pda = Map.get(options, :collection_authority_record_pda)
+
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_pubkey(leaf_owner_pubkey),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate_pubkey),
+         {:ok, _} <- validate_keypair(payer_keypair),
+         {:ok, _} <- validate_optional_keypair(authority_keypair),
+         {:ok, _} <- validate_optional_pubkey(collection_authority_record_pda),
+         {:ok, _} <- MetadataArgs.validate(metadata),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.update_metadata(
+        tree_pubkey,
+        leaf_owner_pubkey,
+        leaf_delegate_pubkey,
+        payer_keypair,
+        authority_keypair,
+        metadata,
+        update_args,
+        collection_authority_record_pda,
+        root,
+        nonce,
+        index,
+        proof,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Redeems a compressed NFT, removing its leaf from the tree and storing it in a
+  voucher account. This is the first step of `decompress/6`.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_keypair`: Keypair of the current owner (binary format), also pays the fees
+    - `leaf`: Map with the current leaf state (root, data_hash, creator_hash, nonce, index)
+    - `proof`: List of proof node hashes from leaf to root (base58 strings), trimmed to the canopy
+    - `leaf_delegate_pubkey` (optional): Public key of the current delegate, defaults to the owner
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  ## Returns
+    - `{:ok, %SendResult{}}` with the signature, slot and commitment reached on success
+    - `{:error, reason}` on failure
+  """
+  def redeem(
+    tree_pubkey,
+    leaf_owner_keypair,
+    %{root: root, data_hash: data_hash, creator_hash: creator_hash, nonce: nonce, index: index},
+    proof,
+    leaf_delegate_pubkey \\ nil,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_keypair(leaf_owner_keypair),
+         {:ok, _} <- validate_optional_pubkey(leaf_delegate_pubkey),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.redeem_compressed_nft(
+        tree_pubkey,
+        leaf_owner_keypair,
+        leaf_delegate_pubkey,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        proof,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Cancels a redeem, putting the leaf back into the tree and closing the voucher.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_keypair`: Keypair of the owner that redeemed the NFT (binary format)
+    - `root`: Current root of the tree (base58 string)
+    - `nonce`: Nonce of the redeemed leaf
+    - `proof`: Proof of the empty leaf at the redeemed index (base58 strings)
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  ## Returns
+    - `{:ok, %SendResult{}}` with the signature, slot and commitment reached on success
+    - `{:error, reason}` on failure
+  """
+  def cancel_redeem(tree_pubkey, leaf_owner_keypair, root, nonce, proof, %SendOptions{} = send_options \\ %SendOptions{}, rpc_client) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_keypair(leaf_owner_keypair),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.cancel_redeem_compressed_nft(tree_pubkey, leaf_owner_keypair, root, nonce, proof, send_options, client)
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Decompresses a redeemed NFT into a regular Token Metadata NFT owned by the leaf owner.
+
+  The mint is the asset id of the leaf. Its metadata and master edition
+  accounts are derived from it, and the token is sent to the owner's
+  associated token account. The tree must allow decompression.
+
+  ## Parameters
+    - `tree_pubkey`: Public key of the merkle tree (string)
+    - `leaf_owner_keypair`: Keypair of the owner that redeemed the NFT (binary format)
+    - `metadata`: the %MetadataArgs{} of the leaf
+    - `nonce`: Nonce of the redeemed leaf
+    - `send_options` (optional): %SendOptions{} with compute budget and confirmation settings
+    - `rpc_client`: handle from `RpcClient.open/2`, or a Solana RPC URL
+
+  ## Returns
+    - `{:ok, %SendResult{}}` with the signature, slot and commitment reached on success
+    - `{:error, reason}` on failure
+  """
+  def decompress(tree_pubkey, leaf_owner_keypair, %MetadataArgs{} = metadata, nonce, %SendOptions{} = send_options \\ %SendOptions{}, rpc_client) do
+    with {:ok, _} <- validate_pubkey(tree_pubkey),
+         {:ok, _} <- validate_keypair(leaf_owner_keypair),
+         {:ok, _} <- MetadataArgs.validate(metadata),
+         {:ok, client} <- RpcClient.resolve(rpc_client) do
+      Native.decompress_compressed_nft(tree_pubkey, leaf_owner_keypair, metadata, nonce, send_options, client)
+    else
+      error -> error
+    end
+  end
+
+  defp validate_pubkey(pubkey) do
+    if Native.validate_pubkey_nif(pubkey),
+      do: {:ok, pubkey},
+      else: {:error, "Invalid pubkey: #{pubkey}"}
+  end
+
+  defp validate_optional_pubkey(nil), do: {:ok, nil}
+  defp validate_optional_pubkey(pubkey), do: validate_pubkey(pubkey)
+
+  defp validate_keypair(keypair) do
+    if byte_size(keypair) == 64,
+      do: {:ok, keypair},
+      else: {:error, "Keypair must be 64 bytes"}
+  end
+
+  defp validate_optional_keypair(nil), do: {:ok, nil}
+  defp validate_optional_keypair(keypair), do: validate_keypair(keypair)
+
+  defp validate_keypair_or_pubkey(key) when byte_size(key) == 64, do: {:ok, key}
+  defp validate_keypair_or_pubkey(key) do
+    if String.valid?(key),
+      do: validate_pubkey(key),
+      else: {:error, "Expected a 64 byte keypair or a base58 pubkey"}
+  end
+
+  defp validate_keypairs(keypairs) do
+    case Enum.find(keypairs, &(byte_size(&1) != 64)) do
+      nil -> {:ok, keypairs}
+      _ -> {:error, "Keypair must be 64 bytes"}
+    end
+  end
+
+  defp validate_encoding(encoding) when encoding in [:base58, :base64], do: {:ok, encoding}
+  defp validate_encoding(encoding), do: {:error, "Invalid encoding: #{inspect(encoding)}"}
 end
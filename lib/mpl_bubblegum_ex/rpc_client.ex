@@ -0,0 +1,76 @@
+defmodule MplBubblegumEx.RpcOptions do
+  @moduledoc """
+  Options for `MplBubblegumEx.RpcClient.open/2`.
+
+  `commitment` is the commitment used for reads and for confirming sent
+  transactions (default: `:finalized`). `confirm_timeout_ms` bounds how long
+  a send waits for its transaction to reach the requested commitment, re-signed
+  attempts included; past it the send returns
+  `{:error, %{kind: :confirmation_timeout}}`. Without it, waiting stops when
+  the transaction's blockhash expires.
+
+  `policy` chooses the endpoint for reads when the client has several:
+  `:fallback` (default) always starts with the first endpoint, `:round_robin`
+  starts with the next one on each request.
+  """
+  defstruct [
+    :commitment,         # :processed, :confirmed or :finalized
+    :timeout_ms,         # Integer - timeout of each RPC request (default: 30_000)
+    :confirm_timeout_ms, # Integer - maximum time to wait for confirmation
+    :policy              # :fallback or :round_robin
+  ]
+
+  @type t :: %__MODULE__{
+    commitment: :processed | :confirmed | :finalized | nil,
+    timeout_ms: non_neg_integer() | nil,
+    confirm_timeout_ms: non_neg_integer() | nil,
+    policy: :fallback | :round_robin | nil
+  }
+end
+
+defmodule MplBubblegumEx.RpcClient do
+  @moduledoc """
+  Reusable connection to a Solana RPC node.
+
+  Open one client and pass it to every call instead of a URL, so the HTTP
+  connection is reused:
+
+      {:ok, client} = RpcClient.open("https://api.devnet.solana.com", %RpcOptions{commitment: :confirmed})
+      NFT.mint(tree, owner, payer, metadata, client)
+
+  A client can have several endpoints. Reads go to one endpoint at a time and
+  fail over to the next when it cannot be reached or reports itself unhealthy,
+  while transactions are sent to every endpoint:
+
+      {:ok, client} = RpcClient.open([primary_url, backup_url], %RpcOptions{policy: :fallback})
+
+  Functions that take a client also accept a URL, in which case a client with
+  the default options is opened for that call only.
+  """
+
+  alias MplBubblegumEx.Native
+  alias MplBubblegumEx.RpcOptions
+
+  @type t :: reference()
+
+  @doc """
+  Opens an RPC client for a URL or a list of endpoint URLs.
+
+  ## Returns
+    - `{:ok, client}` on success
+    - `{:error, :invalid_url}` if the list is empty or a URL is not an http(s) URL
+  """
+  def open(url_or_urls, options \\ %RpcOptions{})
+
+  def open(url, %RpcOptions{} = options) when is_binary(url), do: open([url], options)
+
+  def open(urls, %RpcOptions{} = options) when is_list(urls) do
+    Native.open_rpc_client(urls, options)
+  end
+
+  @doc false
+  def resolve(url) when is_binary(url), do: open(url)
+  def resolve(urls) when is_list(urls), do: open(urls)
+  def resolve(client) when is_reference(client), do: {:ok, client}
+  def resolve(other), do: {:error, "Invalid RPC client: #{inspect(other)}"}
+end
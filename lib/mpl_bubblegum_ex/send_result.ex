@@ -0,0 +1,18 @@
+defmodule MplBubblegumEx.SendResult do
+  @moduledoc """
+  A sent and confirmed transaction. `commitment` is the highest level the
+  transaction had reached when the call returned, which can be above the
+  requested one.
+  """
+  defstruct [
+    :signature,  # String - base58 transaction signature
+    :slot,       # Integer - slot the transaction was processed in
+    :commitment  # :processed, :confirmed or :finalized
+  ]
+
+  @type t :: %__MODULE__{
+    signature: String.t(),
+    slot: non_neg_integer(),
+    commitment: :processed | :confirmed | :finalized
+  }
+end
@@ -0,0 +1,20 @@
+defmodule MplBubblegumEx.SimulationResult do
+  @moduledoc """
+  Outcome of a simulated transaction. `error` is `nil` when the transaction
+  would have succeeded. Otherwise it is the same
+  `%{kind: atom, message: string, details: map}` error a failed send returns,
+  with custom Bubblegum and account compression errors decoded into
+  `details.program_error`.
+  """
+  defstruct [
+    :logs,           # List of program log lines
+    :units_consumed, # Integer - compute units consumed by the transaction
+    :error           # Map - structured transaction error, if the transaction would fail
+  ]
+
+  @type t :: %__MODULE__{
+    logs: [String.t()],
+    units_consumed: non_neg_integer() | nil,
+    error: %{kind: atom(), message: String.t(), details: map()} | nil
+  }
+end
@@ -24,10 +24,11 @@ defmodule MplBubblegumEx.Solana do
   end
 
   @doc """
-  Submits a base58-encoded transaction to Solana
+  Submits a serialized transaction to Solana, base58-encoded unless
+  `encoding` is `:base64`
   """
-  def submit_transaction(tx_base58, rpc_url) do
-    params = [tx_base58, %{"encoding" => "base58"}]
+  def submit_transaction(tx, encoding \\ :base58, rpc_url) do
+    params = [tx, %{"encoding" => Atom.to_string(encoding)}]
 
     response = HTTPoison.post!(
       rpc_url,
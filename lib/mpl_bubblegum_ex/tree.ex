@@ -3,6 +3,9 @@ defmodule MplBubblegumEx.Tree do
   Functions for creating and managing compressed NFT Merkle Trees.
   """
   alias MplBubblegumEx.Native
+  alias MplBubblegumEx.TreeOptions
+  alias MplBubblegumEx.SendOptions
+  alias MplBubblegumEx.RpcClient
 
   @doc """
   Creates a new Merkle Tree configuration on Solana.
@@ -10,36 +13,231 @@ defmodule MplBubblegumEx.Tree do
   ## Parameters
   - `max_depth`: Maximum depth of the Merkle tree (e.g., 14).
   - `max_buffer_size`: Maximum buffer size (e.g., 64).
+  - `canopy_depth` (optional): Number of upper tree levels cached on-chain (default: 0).
+    Must not exceed `max_depth`; a deeper canopy means shorter proofs per transaction.
   - `payer_keypair`: Keypair of the payer (binary format).
   - `merkle_tree_pubkey`: Public key of the Merkle tree (string).
-  - `rpc_url`: Solana RPC URL (e.g., "https://api.devnet.solana.com").
-  - `options`: Optional parameters for tree configuration:
-    - `:public` - Boolean indicating if the tree is public (default: true)
+  - `rpc_client`: handle from `MplBubblegumEx.RpcClient.open/2`, or a Solana RPC URL (e.g., "https://api.devnet.solana.com").
+  - `options`: Optional %TreeOptions{} for tree configuration:
+    - `:public` - Boolean indicating if anyone can mint to the tree (default: false)
+    - `:tree_creator` - Keypair of the tree creator, if different from the payer. A base58
+      pubkey is only accepted when it is the payer, since the tree creator signs
     - `:log_wrapper` - Custom log wrapper program ID
     - `:compression_program` - Custom compression program ID
     - `:system_program` - Custom system program ID
+  - `send_options`: Optional %SendOptions{} with compute budget and confirmation settings.
 
   ## Returns
-  `{:ok, tx_signature}` or `{:error, reason}`
+  `{:ok, %SendResult{}}` or `{:error, reason}`
   """
   def create_tree_config(
     max_depth,
     max_buffer_size,
+    canopy_depth \\ 0,
     payer_keypair,
     merkle_tree_keypair,
-    rpc_url
+    %TreeOptions{} = options \\ %TreeOptions{},
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
   ) do
 
     # Validate inputs before calling Rust function
     with {:ok, _} <- validate_keypair(merkle_tree_keypair),
-         {:ok, _} <- validate_keypair(payer_keypair)
+         {:ok, _} <- validate_keypair(payer_keypair),
+         {:ok, _} <- validate_optional_keypair_or_pubkey(options.tree_creator),
+         {:ok, _} <- validate_optional_pubkey(options.log_wrapper),
+         {:ok, _} <- validate_optional_pubkey(options.compression_program),
+         {:ok, _} <- validate_optional_pubkey(options.system_program),
+         {:ok, client} <- RpcClient.resolve(rpc_client)
     do
       Native.create_tree_config_tx(
         max_depth,
         max_buffer_size,
+        canopy_depth,
         payer_keypair,
         merkle_tree_keypair,
-        rpc_url
+        options,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Builds the create tree transaction without sending it, for submission
+  through another pipeline or co-signing by a wallet.
+
+  ## Parameters
+  - `max_depth`, `max_buffer_size`, `canopy_depth`: as in `create_tree_config/8`.
+  - `payer_pubkey`: Public key of the payer (string).
+  - `merkle_tree_pubkey`: Public key of the new Merkle tree account (string).
+  - `recent_blockhash`: Recent blockhash to build the transaction with (base58 string).
+  - `options`: Optional %TreeOptions{}, as in `create_tree_config/8`. `:tree_creator`
+    may also be a base58 pubkey here, its signature is then left empty.
+  - `build_opts`: Optional map:
+    - `:signers` - Keypairs to sign with (default: []). Required signers not
+      given here are left unsigned, so the transaction is only partially signed.
+    - `:encoding` - `:base58` (default) or `:base64`.
+
+  Rent for the tree account is computed from the default rent parameters.
+
+  ## Returns
+  `{:ok, serialized_tx}` or `{:error, reason}`
+  """
+  def build_create_tree_config(
+    max_depth,
+    max_buffer_size,
+    canopy_depth,
+    payer_pubkey,
+    merkle_tree_pubkey,
+    recent_blockhash,
+    %TreeOptions{} = options \\ %TreeOptions{},
+    build_opts \\ %{}
+  ) do
+    signers = Map.get(build_opts, :signers, [])
+    encoding = Map.get(build_opts, :encoding, :base58)
+
+    with {:ok, _} <- validate_pubkey(payer_pubkey),
+         {:ok, _} <- validate_pubkey(merkle_tree_pubkey),
+         {:ok, _} <- validate_keypairs(signers),
+         {:ok, _} <- validate_optional_keypair_or_pubkey(options.tree_creator),
+         {:ok, _} <- validate_optional_pubkey(options.log_wrapper),
+         {:ok, _} <- validate_optional_pubkey(options.compression_program),
+         {:ok, _} <- validate_optional_pubkey(options.system_program),
+         {:ok, _} <- validate_encoding(encoding)
+    do
+      Native.build_create_tree_config_tx(
+        max_depth,
+        max_buffer_size,
+        canopy_depth,
+        payer_pubkey,
+        merkle_tree_pubkey,
+        options,
+        signers,
+        recent_blockhash,
+        encoding
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Simulates the create tree transaction instead of submitting it.
+
+  Takes the same parameters as `create_tree_config_instructions/6`, plus
+  optional %SendOptions{} and the RPC URL. No keypairs are needed since
+  signatures are not verified during simulation.
+
+  ## Returns
+  `{:ok, %SimulationResult{}}` or `{:error, reason}`. The simulation result is
+  returned even if the transaction would fail.
+  """
+  def simulate_create_tree_config(
+    max_depth,
+    max_buffer_size,
+    canopy_depth,
+    payer_pubkey,
+    merkle_tree_pubkey,
+    %TreeOptions{} = options \\ %TreeOptions{},
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(payer_pubkey),
+         {:ok, _} <- validate_pubkey(merkle_tree_pubkey),
+         {:ok, _} <- validate_optional_keypair_or_pubkey(options.tree_creator),
+         {:ok, _} <- validate_optional_pubkey(options.log_wrapper),
+         {:ok, _} <- validate_optional_pubkey(options.compression_program),
+         {:ok, _} <- validate_optional_pubkey(options.system_program),
+         {:ok, client} <- RpcClient.resolve(rpc_client)
+    do
+      Native.simulate_create_tree_config_tx(
+        max_depth,
+        max_buffer_size,
+        canopy_depth,
+        payer_pubkey,
+        merkle_tree_pubkey,
+        options,
+        send_options,
+        client
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Returns the create_account and CreateTreeConfig instructions as
+  %Instruction{} structs, without building a transaction.
+
+  Takes the same parameters as `build_create_tree_config/8`, minus the
+  blockhash and build options. Both instructions must go in the same
+  transaction, signed by the payer, the Merkle tree keypair and the tree creator.
+
+  ## Returns
+  `{:ok, [create_account_ix, create_tree_config_ix]}` or `{:error, reason}`
+  """
+  def create_tree_config_instructions(
+    max_depth,
+    max_buffer_size,
+    canopy_depth,
+    payer_pubkey,
+    merkle_tree_pubkey,
+    %TreeOptions{} = options \\ %TreeOptions{}
+  ) do
+    with {:ok, _} <- validate_pubkey(payer_pubkey),
+         {:ok, _} <- validate_pubkey(merkle_tree_pubkey),
+         {:ok, _} <- validate_optional_keypair_or_pubkey(options.tree_creator),
+         {:ok, _} <- validate_optional_pubkey(options.log_wrapper),
+         {:ok, _} <- validate_optional_pubkey(options.compression_program),
+         {:ok, _} <- validate_optional_pubkey(options.system_program)
+    do
+      Native.create_tree_config_ix(
+        max_depth,
+        max_buffer_size,
+        canopy_depth,
+        payer_pubkey,
+        merkle_tree_pubkey,
+        options
+      )
+    else
+      error -> error
+    end
+  end
+
+  @doc """
+  Sets the tree delegate, allowing it to mint to the tree in place of the tree creator.
+
+  ## Parameters
+  - `merkle_tree_pubkey`: Public key of the Merkle tree (string).
+  - `tree_creator_keypair`: Keypair of the tree creator (binary format).
+  - `new_tree_delegate_pubkey`: Public key of the new tree delegate (string).
+  - `send_options`: Optional %SendOptions{} with compute budget and confirmation settings.
+  - `rpc_client`: handle from `MplBubblegumEx.RpcClient.open/2`, or a Solana RPC URL.
+
+  ## Returns
+  `{:ok, %SendResult{}}` or `{:error, reason}`
+  """
+  def set_tree_delegate(
+    merkle_tree_pubkey,
+    tree_creator_keypair,
+    new_tree_delegate_pubkey,
+    %SendOptions{} = send_options \\ %SendOptions{},
+    rpc_client
+  ) do
+    with {:ok, _} <- validate_pubkey(merkle_tree_pubkey),
+         {:ok, _} <- validate_keypair(tree_creator_keypair),
+         {:ok, _} <- validate_pubkey(new_tree_delegate_pubkey),
+         {:ok, client} <- RpcClient.resolve(rpc_client)
+    do
+      Native.set_tree_delegate(
+        merkle_tree_pubkey,
+        tree_creator_keypair,
+        new_tree_delegate_pubkey,
+        send_options,
+        client
       )
     else
       error -> error
@@ -50,9 +248,28 @@ defmodule MplBubblegumEx.Tree do
       do: {:ok, pubkey},
       else: {:error, "Invalid pubkey: #{pubkey}"}
   end
+  defp validate_optional_pubkey(nil), do: {:ok, nil}
+  defp validate_optional_pubkey(pubkey), do: validate_pubkey(pubkey)
   defp validate_keypair(keypair) do
     if byte_size(keypair) == 64,
       do: {:ok, keypair},
       else: {:error, "Keypair must be 64 bytes"}
   end
+  defp validate_optional_keypair(nil), do: {:ok, nil}
+  defp validate_optional_keypair(keypair), do: validate_keypair(keypair)
+  defp validate_optional_keypair_or_pubkey(nil), do: {:ok, nil}
+  defp validate_optional_keypair_or_pubkey(key) when byte_size(key) == 64, do: {:ok, key}
+  defp validate_optional_keypair_or_pubkey(key) do
+    if String.valid?(key),
+      do: validate_pubkey(key),
+      else: {:error, "Expected a 64 byte keypair or a base58 pubkey"}
+  end
+  defp validate_keypairs(keypairs) do
+    case Enum.find(keypairs, &(byte_size(&1) != 64)) do
+      nil -> {:ok, keypairs}
+      _ -> {:error, "Keypair must be 64 bytes"}
+    end
+  end
+  defp validate_encoding(encoding) when encoding in [:base58, :base64], do: {:ok, encoding}
+  defp validate_encoding(encoding), do: {:error, "Invalid encoding: #{inspect(encoding)}"}
 end
@@ -7,12 +7,67 @@ defmodule MplBubblegumEx.Native do
 
   def validate_pubkey_nif(_pubkey), do: :erlang.nif_error(:nif_not_loaded)
   def validate_keypair_nif(_keypair), do: :erlang.nif_error(:nif_not_loaded)
-  def create_tree_config_tx(_max_depth,_max_buffer_size,_payer_keypair,_merkle_tree_pubkey,_rpc_url),
+  def open_rpc_client(_urls, _options), do: :erlang.nif_error(:nif_not_loaded)
+  def get_asset_id(_tree_pubkey, _nonce), do: :erlang.nif_error(:nif_not_loaded)
+  def find_asset_nonce(_asset_id, _tree_pubkey, _first_nonce, _last_nonce), do: :erlang.nif_error(:nif_not_loaded)
+  def create_tree_config_tx(_max_depth,_max_buffer_size,_canopy_depth,_payer_keypair,_merkle_tree_pubkey,_options,_send_options, _rpc_client),
     do: :erlang.nif_error(:nif_not_loaded)
-  def mint_compressed_nft_tx(_tree_pubkey, _leaf_owner_pubkey, _payer_keypair, _metadata, _rpc_url),
+  def mint_compressed_nft_tx(_tree_pubkey, _leaf_owner_pubkey, _payer_keypair, _tree_authority_keypair, _metadata, _send_options, _rpc_client),
     do: :erlang.nif_error(:nif_not_loaded)
-  def mint_to_collection_tx(_tree_pubkey, _leaf_owner_pubkey, _payer_keypair, _metadata,_collection_mint, _collection_authority, _collection_authority_record_pda, _rpc_url),
+  def mint_to_collection_tx(_tree_pubkey, _leaf_owner_pubkey, _payer_keypair, _tree_authority_keypair, _metadata,_collection_mint, _collection_authority, _collection_authority_record_pda, _send_options, _rpc_client),
     do: :erlang.nif_error(:nif_not_loaded)
-  def transfer_compressed_nft(_tree_keypair, _leaf_owner_keypair, _new_leaf_owner_pubkey, _asset_id,_root, _data_hash, _creator_hash, _nonce, _index, _rpc_url),
+  def transfer_compressed_nft(_tree, _leaf_owner_pubkey, _leaf_delegate_pubkey, _signer_keypair, _new_leaf_owner_pubkey, _asset_id,_root, _data_hash, _creator_hash, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def mint_compressed_nft_tx_async(_tree_pubkey, _leaf_owner_pubkey, _payer_keypair, _tree_authority_keypair, _metadata, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def mint_to_collection_tx_async(_tree_pubkey, _leaf_owner_pubkey, _payer_keypair, _tree_authority_keypair, _metadata, _collection_mint, _collection_authority, _collection_authority_record_pda, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def transfer_compressed_nft_async(_tree, _leaf_owner_pubkey, _leaf_delegate_pubkey, _signer_keypair, _new_leaf_owner_pubkey, _asset_id, _root, _data_hash, _creator_hash, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def burn_compressed_nft(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _signer_keypair, _root, _data_hash, _creator_hash, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def delegate_compressed_nft(_tree_pubkey, _leaf_owner_keypair, _previous_leaf_delegate_pubkey, _new_leaf_delegate_pubkey, _root, _data_hash, _creator_hash, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def set_tree_delegate(_tree_pubkey, _tree_creator_keypair, _new_tree_delegate_pubkey, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def verify_creator(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _creator_keypair, _metadata, _root, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def unverify_creator(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _creator_keypair, _metadata, _root, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def verify_collection(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _payer_keypair, _tree_authority_keypair, _metadata, _collection_mint, _collection_authority, _collection_authority_record_pda, _collection_metadata, _collection_edition, _root, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def unverify_collection(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _payer_keypair, _tree_authority_keypair, _metadata, _collection_mint, _collection_authority, _collection_authority_record_pda, _collection_metadata, _collection_edition, _root, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def set_and_verify_collection(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _payer_keypair, _tree_authority_keypair, _metadata, _collection_mint, _collection_authority, _collection_authority_record_pda, _collection_metadata, _collection_edition, _root, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def update_metadata(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _payer_keypair, _authority_keypair, _metadata, _update_args, _collection_authority_record_pda, _root, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def redeem_compressed_nft(_tree_pubkey, _leaf_owner_keypair, _leaf_delegate_pubkey, _root, _data_hash, _creator_hash, _nonce, _index, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def cancel_redeem_compressed_nft(_tree_pubkey, _leaf_owner_keypair, _root, _nonce, _proof, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def decompress_compressed_nft(_tree_pubkey, _leaf_owner_keypair, _metadata, _nonce, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def build_create_tree_config_tx(_max_depth, _max_buffer_size, _canopy_depth, _payer_pubkey, _merkle_tree_pubkey, _options, _signer_keypairs, _recent_blockhash, _encoding),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def build_mint_compressed_nft_tx(_tree_pubkey, _leaf_owner_pubkey, _payer_pubkey, _tree_authority_pubkey, _metadata, _signer_keypairs, _recent_blockhash, _encoding),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def build_mint_to_collection_tx(_tree_pubkey, _leaf_owner_pubkey, _payer_pubkey, _tree_authority_pubkey, _metadata, _collection_mint, _collection_authority_pubkey, _collection_authority_record_pda, _signer_keypairs, _recent_blockhash, _encoding),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def build_transfer_compressed_nft(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _signer_pubkey, _new_leaf_owner_pubkey, _root, _data_hash, _creator_hash, _nonce, _index, _proof, _canopy_depth, _signer_keypairs, _recent_blockhash, _encoding),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def create_tree_config_ix(_max_depth, _max_buffer_size, _canopy_depth, _payer_pubkey, _merkle_tree_pubkey, _options),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def mint_compressed_nft_ix(_tree_pubkey, _leaf_owner_pubkey, _payer_pubkey, _tree_authority_pubkey, _metadata),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def mint_to_collection_ix(_tree_pubkey, _leaf_owner_pubkey, _payer_pubkey, _tree_authority_pubkey, _metadata, _collection_mint, _collection_authority_pubkey, _collection_authority_record_pda),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def transfer_compressed_nft_ix(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _signer_pubkey, _new_leaf_owner_pubkey, _root, _data_hash, _creator_hash, _nonce, _index, _proof, _canopy_depth),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def simulate_create_tree_config_tx(_max_depth, _max_buffer_size, _canopy_depth, _payer_pubkey, _merkle_tree_pubkey, _options, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def simulate_mint_compressed_nft_tx(_tree_pubkey, _leaf_owner_pubkey, _payer_pubkey, _tree_authority_pubkey, _metadata, _send_options, _rpc_client),
+    do: :erlang.nif_error(:nif_not_loaded)
+  def simulate_transfer_compressed_nft(_tree_pubkey, _leaf_owner_pubkey, _leaf_delegate_pubkey, _signer_pubkey, _new_leaf_owner_pubkey, _root, _data_hash, _creator_hash, _nonce, _index, _proof, _send_options, _rpc_client),
     do: :erlang.nif_error(:nif_not_loaded)
 end
@@ -0,0 +1,49 @@
+// File: native/mpl_bubblegum_native/src/asset.rs
+
+use mpl_bubblegum::utils::get_asset_id as derive_asset_id;
+use crate::proof::parse_pubkey;
+
+// Define atoms for error handling
+rustler::atoms! {
+    ok,
+    invalid_nonce_range,
+    not_found
+}
+
+// Asset id of the leaf minted with `nonce` in the tree, without calling the
+// RPC node. The nonce of a mint is the tree's `num_minted` before it, so ids
+// can be known before the mint confirms.
+#[rustler::nif]
+pub fn get_asset_id(tree_pubkey_str: &str, nonce: u64) -> Result<(rustler::Atom, String), rustler::Error> {
+    let merkle_tree = parse_pubkey(tree_pubkey_str)?;
+
+    Ok((ok(), derive_asset_id(&merkle_tree, nonce).to_string()))
+}
+
+// Most nonces `find_asset_nonce` checks in one call, which bounds how long a
+// call keeps a dirty CPU scheduler busy
+const MAX_NONCE_RANGE: u64 = 1 << 20;
+
+// Nonce in `first_nonce..=last_nonce` whose asset id in the tree is
+// `asset_id_str`. Each nonce costs a PDA derivation, so this runs on a dirty
+// CPU scheduler, and ranges of more than MAX_NONCE_RANGE nonces are rejected.
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn find_asset_nonce(
+    asset_id_str: &str,
+    tree_pubkey_str: &str,
+    first_nonce: u64,
+    last_nonce: u64
+) -> Result<(rustler::Atom, u64), rustler::Error> {
+    let asset_id = parse_pubkey(asset_id_str)?;
+    let merkle_tree = parse_pubkey(tree_pubkey_str)?;
+
+    if first_nonce > last_nonce || last_nonce - first_nonce >= MAX_NONCE_RANGE {
+        return Err(rustler::Error::Term(Box::new(invalid_nonce_range())));
+    }
+
+    let nonce = (first_nonce..=last_nonce)
+        .find(|nonce| derive_asset_id(&merkle_tree, *nonce) == asset_id)
+        .ok_or_else(|| rustler::Error::Term(Box::new(not_found())))?;
+
+    Ok((ok(), nonce))
+}
@@ -0,0 +1,109 @@
+// File: native/mpl_bubblegum_native/src/burn.rs
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use mpl_bubblegum::{
+    instructions::BurnBuilder,
+    accounts::TreeConfig,
+};
+use crate::rpc::RpcClientResource;
+use std::str::FromStr;
+use rustler::{Binary, ResourceArc};
+use crate::proof::{fetch_canopy_depth, parse_hash, proof_accounts};
+use crate::transaction::{SendOptions, SendResultNif, send_transaction};
+
+// Define atoms for error handling
+rustler::atoms! {
+    ok,
+    error,
+    invalid_keypair,
+    invalid_pubkey,
+    invalid_signer
+}
+
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn burn_compressed_nft(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    signer_keypair: Binary,
+    root_str: &str,
+    data_hash_str: &str,
+    creator_hash_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = match Pubkey::from_str(merkle_tree_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    // Signer is either the leaf owner or the leaf delegate
+    let signer_vec = signer_keypair.as_slice().to_vec();
+    let signer = match Keypair::from_bytes(&signer_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    let leaf_owner = match Pubkey::from_str(leaf_owner_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    // Delegate defaults to the owner when none has been set
+    let leaf_delegate = match leaf_delegate_pubkey_str {
+        Some(delegate_str) => match Pubkey::from_str(&delegate_str) {
+            Ok(pubkey) => pubkey,
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+        },
+        None => leaf_owner
+    };
+
+    let owner_signs = signer.pubkey() == leaf_owner;
+    let delegate_signs = !owner_signs && signer.pubkey() == leaf_delegate;
+    if !owner_signs && !delegate_signs {
+        return Err(rustler::Error::Term(Box::new(invalid_signer())));
+    }
+
+    let root = parse_hash(root_str)?;
+    let data_hash = parse_hash(data_hash_str)?;
+    let creator_hash = parse_hash(creator_hash_str)?;
+
+    // Get PDA for tree_authority
+    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
+
+    // Only the part of the proof not covered by the canopy is sent
+    let canopy_depth = fetch_canopy_depth(&rpc_client, &merkle_tree_pubkey)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    // Build burn instruction
+    let burn_ix = BurnBuilder::new()
+        .tree_config(tree_authority)
+        .leaf_owner(leaf_owner, owner_signs)
+        .leaf_delegate(leaf_delegate, delegate_signs)
+        .merkle_tree(merkle_tree_pubkey)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .add_remaining_accounts(&proof_path)
+        .instruction();
+
+    // Sign, submit and confirm transaction
+    let result = send_transaction(
+        &rpc_client,
+        &[burn_ix],
+        &signer.pubkey(),
+        &[&signer],
+        &send_options
+    )?;
+
+    Ok((ok(), result))
+}
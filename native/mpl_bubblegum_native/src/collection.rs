@@ -0,0 +1,344 @@
+// File: native/mpl_bubblegum_native/src/collection.rs
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use mpl_bubblegum::{
+    instructions::{SetAndVerifyCollectionBuilder, UnverifyCollectionBuilder, VerifyCollectionBuilder},
+    accounts::TreeConfig,
+};
+use crate::rpc::RpcClientResource;
+use std::str::FromStr;
+use rustler::{Binary, ResourceArc};
+use crate::metadata::{
+    MetadataArgsNif, convert_metadata_args, find_master_edition_pda, find_metadata_pda, hash_metadata_args
+};
+use crate::proof::{fetch_canopy_depth, parse_hash, proof_accounts};
+use crate::transaction::{SendOptions, SendResultNif, send_transaction};
+
+// Define atoms for error handling
+rustler::atoms! {
+    ok,
+    error,
+    invalid_keypair,
+    invalid_pubkey
+}
+
+enum CollectionAction {
+    Verify,
+    Unverify,
+    SetAndVerify,
+}
+
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn verify_collection(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    payer_keypair: Binary,
+    tree_authority_keypair: Option<Binary>,
+    metadata_args: MetadataArgsNif,
+    collection_mint_str: &str,
+    collection_authority_keypair: Binary,
+    collection_authority_record_pda_str: Option<String>,
+    collection_metadata_str: Option<String>,
+    collection_edition_str: Option<String>,
+    root_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    update_collection(
+        CollectionAction::Verify,
+        merkle_tree_pubkey_str,
+        leaf_owner_pubkey_str,
+        leaf_delegate_pubkey_str,
+        payer_keypair,
+        tree_authority_keypair,
+        metadata_args,
+        collection_mint_str,
+        collection_authority_keypair,
+        collection_authority_record_pda_str,
+        collection_metadata_str,
+        collection_edition_str,
+        root_str,
+        nonce,
+        index,
+        proof,
+        send_options,
+        rpc_client
+    )
+}
+
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn unverify_collection(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    payer_keypair: Binary,
+    tree_authority_keypair: Option<Binary>,
+    metadata_args: MetadataArgsNif,
+    collection_mint_str: &str,
+    collection_authority_keypair: Binary,
+    collection_authority_record_pda_str: Option<String>,
+    collection_metadata_str: Option<String>,
+    collection_edition_str: Option<String>,
+    root_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    update_collection(
+        CollectionAction::Unverify,
+        merkle_tree_pubkey_str,
+        leaf_owner_pubkey_str,
+        leaf_delegate_pubkey_str,
+        payer_keypair,
+        tree_authority_keypair,
+        metadata_args,
+        collection_mint_str,
+        collection_authority_keypair,
+        collection_authority_record_pda_str,
+        collection_metadata_str,
+        collection_edition_str,
+        root_str,
+        nonce,
+        index,
+        proof,
+        send_options,
+        rpc_client
+    )
+}
+
+// Sets the collection of a leaf and verifies it in one step. Requires the
+// tree creator or tree delegate to sign.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn set_and_verify_collection(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    payer_keypair: Binary,
+    tree_authority_keypair: Option<Binary>,
+    metadata_args: MetadataArgsNif,
+    collection_mint_str: &str,
+    collection_authority_keypair: Binary,
+    collection_authority_record_pda_str: Option<String>,
+    collection_metadata_str: Option<String>,
+    collection_edition_str: Option<String>,
+    root_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    update_collection(
+        CollectionAction::SetAndVerify,
+        merkle_tree_pubkey_str,
+        leaf_owner_pubkey_str,
+        leaf_delegate_pubkey_str,
+        payer_keypair,
+        tree_authority_keypair,
+        metadata_args,
+        collection_mint_str,
+        collection_authority_keypair,
+        collection_authority_record_pda_str,
+        collection_metadata_str,
+        collection_edition_str,
+        root_str,
+        nonce,
+        index,
+        proof,
+        send_options,
+        rpc_client
+    )
+}
+
+fn parse_optional_pubkey(pubkey_str: Option<String>) -> Result<Option<Pubkey>, rustler::Error> {
+    match pubkey_str {
+        Some(pubkey_str) => match Pubkey::from_str(&pubkey_str) {
+            Ok(pubkey) => Ok(Some(pubkey)),
+            Err(_) => Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+        },
+        None => Ok(None)
+    }
+}
+
+// Shared body of the collection NIFs. The leaf's data hash and creator hash
+// are recomputed from the current metadata.
+fn update_collection(
+    action: CollectionAction,
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    payer_keypair: Binary,
+    tree_authority_keypair: Option<Binary>,
+    metadata_args: MetadataArgsNif,
+    collection_mint_str: &str,
+    collection_authority_keypair: Binary,
+    collection_authority_record_pda_str: Option<String>,
+    collection_metadata_str: Option<String>,
+    collection_edition_str: Option<String>,
+    root_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = match Pubkey::from_str(merkle_tree_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    let payer_vec = payer_keypair.as_slice().to_vec();
+    let payer = match Keypair::from_bytes(&payer_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    // Tree creator or tree delegate, defaults to the payer
+    let tree_authority_signer = match tree_authority_keypair {
+        Some(keypair_bin) => match Keypair::from_bytes(keypair_bin.as_slice()) {
+            Ok(keypair) => Some(keypair),
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+        },
+        None => None
+    };
+    let tree_authority_signer = tree_authority_signer.as_ref().unwrap_or(&payer);
+
+    let collection_vec = collection_authority_keypair.as_slice().to_vec();
+    let collection_authority = match Keypair::from_bytes(&collection_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    let leaf_owner = match Pubkey::from_str(leaf_owner_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    let leaf_delegate = parse_optional_pubkey(leaf_delegate_pubkey_str)?.unwrap_or(leaf_owner);
+
+    let collection_mint = match Pubkey::from_str(collection_mint_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    // Collection metadata and edition default to the Token Metadata PDAs of the mint
+    let collection_authority_record = parse_optional_pubkey(collection_authority_record_pda_str)?;
+    let collection_metadata = parse_optional_pubkey(collection_metadata_str)?
+        .unwrap_or_else(|| find_metadata_pda(&collection_mint));
+    let collection_edition = parse_optional_pubkey(collection_edition_str)?
+        .unwrap_or_else(|| find_master_edition_pda(&collection_mint));
+
+    // Convert metadata
+    let metadata = match convert_metadata_args(&metadata_args) {
+        Ok(meta) => meta,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    let (data_hash, creator_hash) = match hash_metadata_args(&metadata) {
+        Ok(hashes) => hashes,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    let root = parse_hash(root_str)?;
+
+    // Get PDA for tree_authority
+    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
+
+    // Only the part of the proof not covered by the canopy is sent
+    let canopy_depth = fetch_canopy_depth(&rpc_client, &merkle_tree_pubkey)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    let collection_ix = match action {
+        CollectionAction::Verify => VerifyCollectionBuilder::new()
+            .tree_config(tree_authority)
+            .leaf_owner(leaf_owner)
+            .leaf_delegate(leaf_delegate)
+            .merkle_tree(merkle_tree_pubkey)
+            .payer(payer.pubkey())
+            .tree_creator_or_delegate(tree_authority_signer.pubkey())
+            .collection_authority(collection_authority.pubkey())
+            .collection_authority_record_pda(collection_authority_record)
+            .collection_mint(collection_mint)
+            .collection_metadata(collection_metadata)
+            .collection_edition(collection_edition)
+            .root(root)
+            .data_hash(data_hash)
+            .creator_hash(creator_hash)
+            .nonce(nonce)
+            .index(index)
+            .metadata(metadata)
+            .add_remaining_accounts(&proof_path)
+            .instruction(),
+        CollectionAction::Unverify => UnverifyCollectionBuilder::new()
+            .tree_config(tree_authority)
+            .leaf_owner(leaf_owner)
+            .leaf_delegate(leaf_delegate)
+            .merkle_tree(merkle_tree_pubkey)
+            .payer(payer.pubkey())
+            .tree_creator_or_delegate(tree_authority_signer.pubkey())
+            .collection_authority(collection_authority.pubkey())
+            .collection_authority_record_pda(collection_authority_record)
+            .collection_mint(collection_mint)
+            .collection_metadata(collection_metadata)
+            .collection_edition(collection_edition)
+            .root(root)
+            .data_hash(data_hash)
+            .creator_hash(creator_hash)
+            .nonce(nonce)
+            .index(index)
+            .metadata(metadata)
+            .add_remaining_accounts(&proof_path)
+            .instruction(),
+        CollectionAction::SetAndVerify => SetAndVerifyCollectionBuilder::new()
+            .tree_config(tree_authority)
+            .leaf_owner(leaf_owner)
+            .leaf_delegate(leaf_delegate)
+            .merkle_tree(merkle_tree_pubkey)
+            .payer(payer.pubkey())
+            .tree_creator_or_delegate(tree_authority_signer.pubkey(), true)
+            .collection_authority(collection_authority.pubkey())
+            .collection_authority_record_pda(collection_authority_record)
+            .collection_mint(collection_mint)
+            .collection_metadata(collection_metadata)
+            .collection_edition(collection_edition)
+            .root(root)
+            .data_hash(data_hash)
+            .creator_hash(creator_hash)
+            .nonce(nonce)
+            .index(index)
+            .metadata(metadata)
+            .collection(collection_mint)
+            .add_remaining_accounts(&proof_path)
+            .instruction(),
+    };
+
+    // Get signers - payer, collection authority and, for set and verify, the tree authority
+    let mut signers = vec![&payer];
+    if matches!(action, CollectionAction::SetAndVerify)
+        && tree_authority_signer.pubkey() != payer.pubkey() {
+        signers.push(tree_authority_signer);
+    }
+    if signers.iter().all(|signer| signer.pubkey() != collection_authority.pubkey()) {
+        signers.push(&collection_authority);
+    }
+
+    let result = send_transaction(
+        &rpc_client,
+        &[collection_ix],
+        &payer.pubkey(),
+        &signers,
+        &send_options
+    )?;
+
+    Ok((ok(), result))
+}
@@ -1,127 +1,140 @@
-use solana_program::system_instruction;
+use solana_program::system_program;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signer::Signer;
-use solana_sdk::{signature::Keypair ,transaction::Transaction, system_instruction::create_account as CreateAccount,}; 
-// use solana_instruction::Instruction as SolanaInstruction; 
-use mpl_bubblegum::instructions::{CreateTreeConfig, CreateTreeConfigBuilder, CreateTreeConfigInstructionArgs};
-use mpl_bubblegum::programs::{MPL_BUBBLEGUM_ID,SPL_ACCOUNT_COMPRESSION_ID,SPL_NOOP_ID}; 
+use solana_sdk::{signature::Keypair , system_instruction::create_account as CreateAccount,}; 
+use solana_sdk::{instruction::Instruction, rent::Rent};
+use mpl_bubblegum::instructions::CreateTreeConfigBuilder;
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID,SPL_NOOP_ID}; 
 use mpl_bubblegum::accounts::TreeConfig;  
-use solana_client::rpc_client::RpcClient;
+use crate::rpc::RpcClientResource;
 use std::str::FromStr;  
 use spl_account_compression::{state::CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1, ConcurrentMerkleTree};
-use rustler::{atoms, nif, Binary, NifStruct};
-use bs58;
-use bincode::serialize;
-use crate::valid_depth_size_pairs::{is_valid_pair, get_valid_pairs_string};
+use rustler::{Binary, Env, NifStruct, ResourceArc};
+use crate::proof::canopy_size;
+use crate::error::client_error;
+use crate::instruction::{InstructionNif, convert_instruction};
+use crate::transaction::{
+    SendOptions, SendResultNif, SimulationResultNif, TxEncoding, build_transaction, encode_transaction, parse_blockhash, parse_signers,
+    send_transaction, simulate_transaction,
+};
 
 rustler::atoms!{
     ok,
     error,
     invalid_keypair,
     invalid_pubkey,
-    rpc_error,
     serialization_error,
     instruction_error,
     invalid_tree_parameters
 }
 
-// #[derive(NifStruct)]
-// #[module = "MplBubblegumEx.TreeOptions"]
+#[derive(NifStruct)]
+#[module = "MplBubblegumEx.TreeOptions"]
+pub struct TreeOptions<'a> {
+    pub public: Option<bool>,
+    // Keypair, or a base58 public key where the tree creator does not sign here
+    pub tree_creator: Option<Binary<'a>>,
+    pub log_wrapper: Option<String>,
+    pub compression_program: Option<String>,
+    pub system_program: Option<String>
+}
 
-// struct TreeOptions{
-//     public: Option<bool>,
-//     log_wrapper:Option<String>,
-//     compression_program:Option<String>,
-//     system_program:Option<String>
-// }
+// Tree options after parsing, with program IDs defaulted
+pub struct ResolvedTreeOptions {
+    public: Option<bool>,
+    tree_creator: Option<TreeCreator>,
+    log_wrapper: Pubkey,
+    compression_program: Pubkey,
+    system_program: Pubkey
+}
 
-// Helper function with const generics for depth and buffer size
-fn create_tree_with_const<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>(
-    payer: Keypair, 
-    merkle_tree : Keypair, 
-    rpc_url : &str, 
-) -> Result<(rustler::Atom,String), rustler::Error> {
+// Tree creator given either as a keypair or as a public key only
+enum TreeCreator {
+    Keypair(Keypair),
+    Pubkey(Pubkey)
+}
 
-        let account_size = CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 + 
-            std::mem::size_of::<ConcurrentMerkleTree<{ MAX_DEPTH }, { MAX_BUFFER_SIZE }>>();
+impl TreeCreator {
+    // A 64 byte binary is a keypair, anything else a base58 public key
+    fn parse(tree_creator: &Binary) -> Result<TreeCreator, rustler::Error> {
+        if tree_creator.len() == 64 {
+            return Keypair::from_bytes(tree_creator.as_slice())
+                .map(TreeCreator::Keypair)
+                .map_err(|_| rustler::Error::Term(Box::new(invalid_keypair())));
+        }
 
-        // Generate PDA for tree config
-        let (tree_config_pda, _) = TreeConfig::find_pda(&merkle_tree.pubkey());
-        
-        let compression_program = SPL_ACCOUNT_COMPRESSION_ID;
+        std::str::from_utf8(tree_creator.as_slice())
+            .ok()
+            .and_then(|pubkey_str| Pubkey::from_str(pubkey_str).ok())
+            .map(TreeCreator::Pubkey)
+            .ok_or(rustler::Error::Term(Box::new(invalid_pubkey())))
+    }
 
-        let rpc_client = RpcClient::new(rpc_url);
-        let rent = rpc_client.get_minimum_balance_for_rent_exemption(account_size)
-           .map_err(|_| rustler::Error::Term(Box::new(rpc_error())))?;
-        // Build accounts
-        let create_account_ix = CreateAccount(
-            &payer.pubkey(),
-            &merkle_tree.pubkey(),
-            rent,
-            account_size as u64, 
-            &compression_program
-        );
+    fn pubkey(&self) -> Pubkey {
+        match self {
+            TreeCreator::Keypair(keypair) => keypair.pubkey(),
+            TreeCreator::Pubkey(pubkey) => *pubkey
+        }
+    }
 
-        let create_tree_ix = CreateTreeConfigBuilder::new()
-            .tree_config(tree_config_pda)
-            .merkle_tree(merkle_tree.pubkey())
-            .payer(payer.pubkey())
-            .tree_creator(payer.pubkey())
-            .max_depth(MAX_DEPTH as u32)
-            .max_buffer_size(MAX_BUFFER_SIZE as u32)
-            .instruction();
-
-        // Build transaction
-        let recent_blockhash = rpc_client.get_latest_blockhash()
-            .map_err(|_| rustler::Error::Term(Box::new(rpc_error())))?;
-
-        let transaction = Transaction::new_signed_with_payer(
-            &[create_account_ix, create_tree_ix],
-            Some(&payer.pubkey()),
-            &[&payer,&merkle_tree],
-            recent_blockhash,
-        );
+    fn keypair(&self) -> Option<&Keypair> {
+        match self {
+            TreeCreator::Keypair(keypair) => Some(keypair),
+            TreeCreator::Pubkey(_) => None
+        }
+    }
+}
+
+fn parse_program_id(program_id: &Option<String>, default: Pubkey) -> Result<Pubkey, rustler::Error> {
+    match program_id {
+        Some(id_str) => Pubkey::from_str(id_str)
+            .map_err(|_| rustler::Error::Term(Box::new(invalid_pubkey()))),
+        None => Ok(default)
+    }
+}
 
-        let signature = rpc_client.send_and_confirm_transaction(&transaction)
-        .map_err(|_| rustler::Error::Term(Box::new(rpc_error())))?;
+fn resolve_tree_options(options: &TreeOptions) -> Result<ResolvedTreeOptions, rustler::Error> {
+    let tree_creator = match &options.tree_creator {
+        Some(tree_creator) => Some(TreeCreator::parse(tree_creator)?),
+        None => None
+    };
 
-        Ok((ok(), signature.to_string()))
+    Ok(ResolvedTreeOptions {
+        public: options.public,
+        tree_creator,
+        log_wrapper: parse_program_id(&options.log_wrapper, SPL_NOOP_ID)?,
+        compression_program: parse_program_id(&options.compression_program, SPL_ACCOUNT_COMPRESSION_ID)?,
+        system_program: parse_program_id(&options.system_program, system_program::ID)?
+    })
 }
 
-#[rustler::nif]
-pub fn create_tree_config_tx(
+// Helper function with const generics for depth and buffer size.
+// The canopy is stored after the tree and is included in the rent
+fn tree_account_size_with_const<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>(
+    canopy_depth: u32,
+) -> usize {
+        CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 + 
+            std::mem::size_of::<ConcurrentMerkleTree<{ MAX_DEPTH }, { MAX_BUFFER_SIZE }>>() +
+            canopy_size(canopy_depth)
+}
+
+// Size of the merkle tree account, rejecting unsupported depth/buffer pairs
+fn tree_account_size(
     max_depth: u32,
     max_buffer_size: u32,
-    payer_keypair: Binary,
-    merkle_tree_keypair : Binary,
-    rpc_url: &str
-    ) -> Result<(rustler::Atom,String), rustler::Error>{
-        
-        // if !is_valid_pair(max_depth, max_buffer_size) {
-        //     let valid_pairs = get_valid_pairs_string();
-        //     return Err(rustler::Error::Term(Box::new(format!(
-        //         "Invalid depth/buffer combination: ({}, {}). Valid combinations are: {}",
-        //         max_depth, max_buffer_size, valid_pairs
-        //     ))));
-        // }
-        let payer_vec = payer_keypair.as_slice().to_vec();
-        let payer: Keypair = match Keypair::from_bytes(&payer_vec) {
-            Ok(keypair) => keypair,
-            Err(_)=>return Err(rustler::Error::Term(Box::new(invalid_keypair())))  
-        };
-
-        let merkle_tree: Keypair = match Keypair::from_bytes(&merkle_tree_keypair.as_slice().to_vec()) {
-            Ok(keypair) => keypair,
-            Err(_)=>return Err(rustler::Error::Term(Box::new(invalid_keypair())))  
-        };
+    canopy_depth: u32,
+) -> Result<usize, rustler::Error> {
+        if canopy_depth > max_depth {
+            return Err(rustler::Error::Term(Box::new(invalid_tree_parameters())));
+        }
 
         macro_rules! generate_tree_config_match {
             ($(($depth:expr, $buffer:expr)),* $(,)?) => {
                 match (max_depth, max_buffer_size) {
                     $(
-                        ($depth, $buffer) => create_tree_with_const::<{$depth as usize}, {$buffer as usize}>(
-                            payer, merkle_tree, rpc_url
-                        ),
+                        ($depth, $buffer) => Ok(tree_account_size_with_const::<{$depth as usize}, {$buffer as usize}>(
+                            canopy_depth
+                        )),
                     )*
                     _ => Err(rustler::Error::Term(Box::new(invalid_tree_parameters())))
                 }
@@ -147,3 +160,258 @@ pub fn create_tree_config_tx(
             (14, 2048), (20, 2048), (24, 2048), (26, 2048), (30, 2048),
         )
 }
+
+// Allocates the merkle tree account and initializes its tree config
+pub fn create_tree_instructions(
+    max_depth: u32,
+    max_buffer_size: u32,
+    account_size: usize,
+    rent: u64,
+    payer: &Pubkey,
+    merkle_tree: &Pubkey,
+    tree_creator: &Pubkey,
+    options: &ResolvedTreeOptions,
+) -> Vec<Instruction> {
+        // Generate PDA for tree config
+        let (tree_config_pda, _) = TreeConfig::find_pda(merkle_tree);
+        
+        // The tree account is owned by whichever compression program is in use
+        let compression_program = options.compression_program;
+
+        // Build accounts
+        let create_account_ix = CreateAccount(
+            payer,
+            merkle_tree,
+            rent,
+            account_size as u64, 
+            &compression_program
+        );
+
+        let mut create_tree_builder = CreateTreeConfigBuilder::new();
+        create_tree_builder
+            .tree_config(tree_config_pda)
+            .merkle_tree(*merkle_tree)
+            .payer(*payer)
+            .tree_creator(*tree_creator)
+            .log_wrapper(options.log_wrapper)
+            .compression_program(compression_program)
+            .system_program(options.system_program)
+            .max_depth(max_depth)
+            .max_buffer_size(max_buffer_size);
+
+        if let Some(public) = options.public {
+            create_tree_builder.public(public);
+        }
+
+        vec![create_account_ix, create_tree_builder.instruction()]
+}
+
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn create_tree_config_tx(
+    max_depth: u32,
+    max_buffer_size: u32,
+    canopy_depth: u32,
+    payer_keypair: Binary,
+    merkle_tree_keypair : Binary,
+    options: TreeOptions,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+    ) -> Result<(rustler::Atom, SendResultNif), rustler::Error>{
+        let account_size = tree_account_size(max_depth, max_buffer_size, canopy_depth)?;
+
+        let payer_vec = payer_keypair.as_slice().to_vec();
+        let payer: Keypair = match Keypair::from_bytes(&payer_vec) {
+            Ok(keypair) => keypair,
+            Err(_)=>return Err(rustler::Error::Term(Box::new(invalid_keypair())))  
+        };
+
+        let merkle_tree: Keypair = match Keypair::from_bytes(merkle_tree_keypair.as_slice()) {
+            Ok(keypair) => keypair,
+            Err(_)=>return Err(rustler::Error::Term(Box::new(invalid_keypair())))  
+        };
+
+        let options = resolve_tree_options(&options)?;
+
+        let rent = rpc_client.get_minimum_balance_for_rent_exemption(account_size)
+           .map_err(client_error)?;
+
+        // Tree creator defaults to the payer and has to sign unless it is the payer
+        let tree_creator = match options.tree_creator.as_ref() {
+            Some(tree_creator) if tree_creator.pubkey() == payer.pubkey() => &payer,
+            Some(tree_creator) => tree_creator.keypair()
+                .ok_or(rustler::Error::Term(Box::new(invalid_keypair())))?,
+            None => &payer
+        };
+
+        let instructions = create_tree_instructions(
+            max_depth,
+            max_buffer_size,
+            account_size,
+            rent,
+            &payer.pubkey(),
+            &merkle_tree.pubkey(),
+            &tree_creator.pubkey(),
+            &options
+        );
+
+        // Tree creator only signs separately when it differs from the payer
+        let mut signers = vec![&payer, &merkle_tree];
+        if tree_creator.pubkey() != payer.pubkey() {
+            signers.push(tree_creator);
+        }
+
+        let result = send_transaction(
+            &rpc_client,
+            &instructions,
+            &payer.pubkey(),
+            &signers,
+            &send_options
+        )?;
+
+        Ok((ok(), result))
+}
+
+// Builds the create tree transaction without sending it. It is signed by
+// whichever of `signer_keypairs` are required (typically the merkle tree
+// keypair), the other signatures are left empty. Rent is computed from the
+// default rent parameters since no RPC call is made.
+#[rustler::nif]
+pub fn build_create_tree_config_tx(
+    max_depth: u32,
+    max_buffer_size: u32,
+    canopy_depth: u32,
+    payer_pubkey_str: &str,
+    merkle_tree_pubkey_str: &str,
+    options: TreeOptions,
+    signer_keypairs: Vec<Binary>,
+    recent_blockhash_str: &str,
+    encoding: TxEncoding
+    ) -> Result<(rustler::Atom,String), rustler::Error>{
+        let account_size = tree_account_size(max_depth, max_buffer_size, canopy_depth)?;
+
+        let payer = Pubkey::from_str(payer_pubkey_str)
+            .map_err(|_| rustler::Error::Term(Box::new(invalid_pubkey())))?;
+        let merkle_tree = Pubkey::from_str(merkle_tree_pubkey_str)
+            .map_err(|_| rustler::Error::Term(Box::new(invalid_pubkey())))?;
+
+        let options = resolve_tree_options(&options)?;
+        let mut signers = parse_signers(&signer_keypairs)?;
+        let recent_blockhash = parse_blockhash(recent_blockhash_str)?;
+
+        // Tree creator defaults to the payer and signs when given as a keypair
+        let tree_creator = match options.tree_creator.as_ref() {
+            Some(tree_creator) => tree_creator.pubkey(),
+            None => payer
+        };
+        if let Some(keypair) = options.tree_creator.as_ref().and_then(TreeCreator::keypair) {
+            if signers.iter().all(|signer| signer.pubkey() != tree_creator) {
+                signers.push(keypair.insecure_clone());
+            }
+        }
+
+        let rent = Rent::default().minimum_balance(account_size);
+        let instructions = create_tree_instructions(
+            max_depth,
+            max_buffer_size,
+            account_size,
+            rent,
+            &payer,
+            &merkle_tree,
+            &tree_creator,
+            &options
+        );
+
+        let transaction = build_transaction(&instructions, &payer, &signers, recent_blockhash)?;
+
+        Ok((ok(), encode_transaction(&transaction, encoding)?))
+}
+
+// Simulates the create tree transaction instead of submitting it. Takes
+// public keys only, since signatures are not verified during simulation.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn simulate_create_tree_config_tx(
+    max_depth: u32,
+    max_buffer_size: u32,
+    canopy_depth: u32,
+    payer_pubkey_str: &str,
+    merkle_tree_pubkey_str: &str,
+    options: TreeOptions,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+    ) -> Result<(rustler::Atom,SimulationResultNif), rustler::Error>{
+        let account_size = tree_account_size(max_depth, max_buffer_size, canopy_depth)?;
+
+        let payer = Pubkey::from_str(payer_pubkey_str)
+            .map_err(|_| rustler::Error::Term(Box::new(invalid_pubkey())))?;
+        let merkle_tree = Pubkey::from_str(merkle_tree_pubkey_str)
+            .map_err(|_| rustler::Error::Term(Box::new(invalid_pubkey())))?;
+
+        let options = resolve_tree_options(&options)?;
+
+        // Tree creator defaults to the payer
+        let tree_creator = match options.tree_creator.as_ref() {
+            Some(tree_creator) => tree_creator.pubkey(),
+            None => payer
+        };
+
+        let rent = rpc_client.get_minimum_balance_for_rent_exemption(account_size)
+           .map_err(client_error)?;
+
+        let instructions = create_tree_instructions(
+            max_depth,
+            max_buffer_size,
+            account_size,
+            rent,
+            &payer,
+            &merkle_tree,
+            &tree_creator,
+            &options
+        );
+
+        let simulation = simulate_transaction(&rpc_client, &instructions, &payer, &send_options)?;
+
+        Ok((ok(), simulation))
+}
+
+// Returns the create_account and CreateTreeConfig instructions without
+// building a transaction. Both must be sent together, signed by the payer,
+// the merkle tree keypair and the tree creator.
+#[rustler::nif]
+pub fn create_tree_config_ix<'a>(
+    env: Env<'a>,
+    max_depth: u32,
+    max_buffer_size: u32,
+    canopy_depth: u32,
+    payer_pubkey_str: &str,
+    merkle_tree_pubkey_str: &str,
+    options: TreeOptions
+    ) -> Result<(rustler::Atom,Vec<InstructionNif<'a>>), rustler::Error>{
+        let account_size = tree_account_size(max_depth, max_buffer_size, canopy_depth)?;
+
+        let payer = Pubkey::from_str(payer_pubkey_str)
+            .map_err(|_| rustler::Error::Term(Box::new(invalid_pubkey())))?;
+        let merkle_tree = Pubkey::from_str(merkle_tree_pubkey_str)
+            .map_err(|_| rustler::Error::Term(Box::new(invalid_pubkey())))?;
+
+        let options = resolve_tree_options(&options)?;
+
+        // Tree creator defaults to the payer
+        let tree_creator = match options.tree_creator.as_ref() {
+            Some(tree_creator) => tree_creator.pubkey(),
+            None => payer
+        };
+
+        let rent = Rent::default().minimum_balance(account_size);
+        let instructions = create_tree_instructions(
+            max_depth,
+            max_buffer_size,
+            account_size,
+            rent,
+            &payer,
+            &merkle_tree,
+            &tree_creator,
+            &options
+        );
+
+        Ok((ok(), instructions.iter().map(|ix| convert_instruction(env, ix)).collect()))
+}
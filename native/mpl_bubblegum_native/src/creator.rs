@@ -0,0 +1,197 @@
+// File: native/mpl_bubblegum_native/src/creator.rs
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use mpl_bubblegum::{
+    instructions::{UnverifyCreatorBuilder, VerifyCreatorBuilder},
+    accounts::TreeConfig,
+};
+use crate::rpc::RpcClientResource;
+use std::str::FromStr;
+use rustler::{Binary, ResourceArc};
+use crate::metadata::{MetadataArgsNif, convert_metadata_args, hash_metadata_args};
+use crate::proof::{fetch_canopy_depth, parse_hash, proof_accounts};
+use crate::transaction::{SendOptions, SendResultNif, send_transaction};
+
+// Define atoms for error handling
+rustler::atoms! {
+    ok,
+    error,
+    invalid_keypair,
+    invalid_pubkey,
+    invalid_creator
+}
+
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn verify_creator(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    creator_keypair: Binary,
+    metadata_args: MetadataArgsNif,
+    root_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    set_creator_verified(
+        true,
+        merkle_tree_pubkey_str,
+        leaf_owner_pubkey_str,
+        leaf_delegate_pubkey_str,
+        creator_keypair,
+        metadata_args,
+        root_str,
+        nonce,
+        index,
+        proof,
+        send_options,
+        rpc_client
+    )
+}
+
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn unverify_creator(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    creator_keypair: Binary,
+    metadata_args: MetadataArgsNif,
+    root_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    set_creator_verified(
+        false,
+        merkle_tree_pubkey_str,
+        leaf_owner_pubkey_str,
+        leaf_delegate_pubkey_str,
+        creator_keypair,
+        metadata_args,
+        root_str,
+        nonce,
+        index,
+        proof,
+        send_options,
+        rpc_client
+    )
+}
+
+// Shared body of verify_creator and unverify_creator. The leaf's data hash and
+// creator hash are recomputed from the current metadata.
+fn set_creator_verified(
+    verified: bool,
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    creator_keypair: Binary,
+    metadata_args: MetadataArgsNif,
+    root_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = match Pubkey::from_str(merkle_tree_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    // Creator signs and pays
+    let creator_vec = creator_keypair.as_slice().to_vec();
+    let creator = match Keypair::from_bytes(&creator_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    let leaf_owner = match Pubkey::from_str(leaf_owner_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    let leaf_delegate = match leaf_delegate_pubkey_str {
+        Some(delegate_str) => match Pubkey::from_str(&delegate_str) {
+            Ok(pubkey) => pubkey,
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+        },
+        None => leaf_owner
+    };
+
+    // Convert metadata
+    let metadata = match convert_metadata_args(&metadata_args) {
+        Ok(meta) => meta,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    // The signing creator must be listed in the metadata
+    if !metadata.creators.iter().any(|c| c.address == creator.pubkey()) {
+        return Err(rustler::Error::Term(Box::new(invalid_creator())));
+    }
+
+    let (data_hash, creator_hash) = match hash_metadata_args(&metadata) {
+        Ok(hashes) => hashes,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    let root = parse_hash(root_str)?;
+
+    // Get PDA for tree_authority
+    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
+
+    // Only the part of the proof not covered by the canopy is sent
+    let canopy_depth = fetch_canopy_depth(&rpc_client, &merkle_tree_pubkey)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    let creator_ix = if verified {
+        VerifyCreatorBuilder::new()
+            .tree_config(tree_authority)
+            .leaf_owner(leaf_owner)
+            .leaf_delegate(leaf_delegate)
+            .merkle_tree(merkle_tree_pubkey)
+            .payer(creator.pubkey())
+            .creator(creator.pubkey())
+            .root(root)
+            .data_hash(data_hash)
+            .creator_hash(creator_hash)
+            .nonce(nonce)
+            .index(index)
+            .metadata(metadata)
+            .add_remaining_accounts(&proof_path)
+            .instruction()
+    } else {
+        UnverifyCreatorBuilder::new()
+            .tree_config(tree_authority)
+            .leaf_owner(leaf_owner)
+            .leaf_delegate(leaf_delegate)
+            .merkle_tree(merkle_tree_pubkey)
+            .payer(creator.pubkey())
+            .creator(creator.pubkey())
+            .root(root)
+            .data_hash(data_hash)
+            .creator_hash(creator_hash)
+            .nonce(nonce)
+            .index(index)
+            .metadata(metadata)
+            .add_remaining_accounts(&proof_path)
+            .instruction()
+    };
+
+    let result = send_transaction(
+        &rpc_client,
+        &[creator_ix],
+        &creator.pubkey(),
+        &[&creator],
+        &send_options
+    )?;
+
+    Ok((ok(), result))
+}
@@ -0,0 +1,227 @@
+// File: native/mpl_bubblegum_native/src/decompress.rs
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use solana_program::pubkey;
+use mpl_bubblegum::{
+    instructions::{CancelRedeemBuilder, DecompressV1Builder, RedeemBuilder},
+    accounts::TreeConfig,
+    programs::MPL_BUBBLEGUM_ID,
+    utils::get_asset_id,
+};
+use crate::rpc::RpcClientResource;
+use std::str::FromStr;
+use rustler::{Binary, ResourceArc};
+use crate::metadata::{MetadataArgsNif, convert_metadata_args, find_master_edition_pda, find_metadata_pda};
+use crate::proof::{fetch_canopy_depth, parse_hash, proof_accounts};
+use crate::transaction::{SendOptions, SendResultNif, send_transaction};
+
+const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+// Define atoms for error handling
+rustler::atoms! {
+    ok,
+    error,
+    invalid_keypair,
+    invalid_pubkey
+}
+
+// Voucher PDA of a redeemed leaf. Derived here because `Voucher::find_pda`
+// seeds the nonce as a string while the program uses its little-endian bytes.
+fn find_voucher_pda(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"voucher", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &MPL_BUBBLEGUM_ID,
+    ).0
+}
+
+// Associated token account of `owner` for `mint`
+fn find_associated_token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    ).0
+}
+
+// Removes the leaf from the tree and stores it in a voucher account, the
+// first step of decompressing an NFT. Signed and paid by the leaf owner.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn redeem_compressed_nft(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_keypair: Binary,
+    leaf_delegate_pubkey_str: Option<String>,
+    root_str: &str,
+    data_hash_str: &str,
+    creator_hash_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = match Pubkey::from_str(merkle_tree_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    let leaf_owner_vec = leaf_owner_keypair.as_slice().to_vec();
+    let leaf_owner = match Keypair::from_bytes(&leaf_owner_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    // Delegate defaults to the owner when none has been set
+    let leaf_delegate = match leaf_delegate_pubkey_str {
+        Some(delegate_str) => match Pubkey::from_str(&delegate_str) {
+            Ok(pubkey) => pubkey,
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+        },
+        None => leaf_owner.pubkey()
+    };
+
+    let root = parse_hash(root_str)?;
+    let data_hash = parse_hash(data_hash_str)?;
+    let creator_hash = parse_hash(creator_hash_str)?;
+
+    // Get PDA for tree_authority
+    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
+
+    // Only the part of the proof not covered by the canopy is sent
+    let canopy_depth = fetch_canopy_depth(&rpc_client, &merkle_tree_pubkey)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    let redeem_ix = RedeemBuilder::new()
+        .tree_config(tree_authority)
+        .leaf_owner(leaf_owner.pubkey())
+        .leaf_delegate(leaf_delegate)
+        .merkle_tree(merkle_tree_pubkey)
+        .voucher(find_voucher_pda(&merkle_tree_pubkey, nonce))
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .add_remaining_accounts(&proof_path)
+        .instruction();
+
+    let result = send_transaction(
+        &rpc_client,
+        &[redeem_ix],
+        &leaf_owner.pubkey(),
+        &[&leaf_owner],
+        &send_options
+    )?;
+
+    Ok((ok(), result))
+}
+
+// Puts a redeemed leaf back into the tree and closes its voucher. `root_str`
+// and `proof` are those of the now empty leaf at the voucher's index.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn cancel_redeem_compressed_nft(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_keypair: Binary,
+    root_str: &str,
+    nonce: u64,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = match Pubkey::from_str(merkle_tree_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    let leaf_owner_vec = leaf_owner_keypair.as_slice().to_vec();
+    let leaf_owner = match Keypair::from_bytes(&leaf_owner_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    let root = parse_hash(root_str)?;
+
+    // Get PDA for tree_authority
+    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
+
+    // Only the part of the proof not covered by the canopy is sent
+    let canopy_depth = fetch_canopy_depth(&rpc_client, &merkle_tree_pubkey)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    let cancel_redeem_ix = CancelRedeemBuilder::new()
+        .tree_config(tree_authority)
+        .leaf_owner(leaf_owner.pubkey())
+        .merkle_tree(merkle_tree_pubkey)
+        .voucher(find_voucher_pda(&merkle_tree_pubkey, nonce))
+        .root(root)
+        .add_remaining_accounts(&proof_path)
+        .instruction();
+
+    let result = send_transaction(
+        &rpc_client,
+        &[cancel_redeem_ix],
+        &leaf_owner.pubkey(),
+        &[&leaf_owner],
+        &send_options
+    )?;
+
+    Ok((ok(), result))
+}
+
+// Turns a redeemed leaf into a regular Token Metadata NFT. The mint is the
+// asset id of the leaf; its metadata and master edition accounts are created
+// from `metadata_args`, which must match the leaf's data hash.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn decompress_compressed_nft(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_keypair: Binary,
+    metadata_args: MetadataArgsNif,
+    nonce: u64,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = match Pubkey::from_str(merkle_tree_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    let leaf_owner_vec = leaf_owner_keypair.as_slice().to_vec();
+    let leaf_owner = match Keypair::from_bytes(&leaf_owner_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    // Convert metadata
+    let metadata = match convert_metadata_args(&metadata_args) {
+        Ok(meta) => meta,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    // The mint is the asset id and its authority a Bubblegum PDA seeded by the mint
+    let mint = get_asset_id(&merkle_tree_pubkey, nonce);
+    let (mint_authority, _) = Pubkey::find_program_address(&[mint.as_ref()], &MPL_BUBBLEGUM_ID);
+
+    let decompress_ix = DecompressV1Builder::new()
+        .voucher(find_voucher_pda(&merkle_tree_pubkey, nonce))
+        .leaf_owner(leaf_owner.pubkey())
+        .token_account(find_associated_token_account(&leaf_owner.pubkey(), &mint))
+        .mint(mint)
+        .mint_authority(mint_authority)
+        .metadata_account(find_metadata_pda(&mint))
+        .master_edition(find_master_edition_pda(&mint))
+        .metadata(metadata)
+        .instruction();
+
+    let result = send_transaction(
+        &rpc_client,
+        &[decompress_ix],
+        &leaf_owner.pubkey(),
+        &[&leaf_owner],
+        &send_options
+    )?;
+
+    Ok((ok(), result))
+}
@@ -0,0 +1,108 @@
+// File: native/mpl_bubblegum_native/src/delegate.rs
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use mpl_bubblegum::{
+    instructions::DelegateBuilder,
+    accounts::TreeConfig,
+};
+use crate::rpc::RpcClientResource;
+use std::str::FromStr;
+use rustler::{Binary, ResourceArc};
+use crate::proof::{fetch_canopy_depth, parse_hash, proof_accounts};
+use crate::transaction::{SendOptions, SendResultNif, send_transaction};
+
+// Define atoms for error handling
+rustler::atoms! {
+    ok,
+    error,
+    invalid_keypair,
+    invalid_pubkey
+}
+
+// Sets a new leaf delegate. Passing `nil` as the new delegate revokes the
+// current one by resetting the delegate back to the owner.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn delegate_compressed_nft(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_keypair: Binary,
+    previous_leaf_delegate_pubkey_str: Option<String>,
+    new_leaf_delegate_pubkey_str: Option<String>,
+    root_str: &str,
+    data_hash_str: &str,
+    creator_hash_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = match Pubkey::from_str(merkle_tree_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    // Only the leaf owner can change the delegate
+    let leaf_owner_vec = leaf_owner_keypair.as_slice().to_vec();
+    let leaf_owner = match Keypair::from_bytes(&leaf_owner_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    let previous_leaf_delegate = match previous_leaf_delegate_pubkey_str {
+        Some(delegate_str) => match Pubkey::from_str(&delegate_str) {
+            Ok(pubkey) => pubkey,
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+        },
+        None => leaf_owner.pubkey()
+    };
+
+    // Revoking sets the delegate back to the owner
+    let new_leaf_delegate = match new_leaf_delegate_pubkey_str {
+        Some(delegate_str) => match Pubkey::from_str(&delegate_str) {
+            Ok(pubkey) => pubkey,
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+        },
+        None => leaf_owner.pubkey()
+    };
+
+    let root = parse_hash(root_str)?;
+    let data_hash = parse_hash(data_hash_str)?;
+    let creator_hash = parse_hash(creator_hash_str)?;
+
+    // Get PDA for tree_authority
+    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
+
+    // Only the part of the proof not covered by the canopy is sent
+    let canopy_depth = fetch_canopy_depth(&rpc_client, &merkle_tree_pubkey)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    // Build delegate instruction
+    let delegate_ix = DelegateBuilder::new()
+        .tree_config(tree_authority)
+        .leaf_owner(leaf_owner.pubkey())
+        .previous_leaf_delegate(previous_leaf_delegate)
+        .new_leaf_delegate(new_leaf_delegate)
+        .merkle_tree(merkle_tree_pubkey)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .add_remaining_accounts(&proof_path)
+        .instruction();
+
+    // Sign, submit and confirm transaction
+    let result = send_transaction(
+        &rpc_client,
+        &[delegate_ix],
+        &leaf_owner.pubkey(),
+        &[&leaf_owner],
+        &send_options
+    )?;
+
+    Ok((ok(), result))
+}
@@ -0,0 +1,299 @@
+// File: native/mpl_bubblegum_native/src/error.rs
+
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::{RpcError, RpcResponseErrorData},
+};
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::TransactionError,
+};
+use mpl_bubblegum::{
+    errors::{MplBubblegumError, SplAccountCompressionError},
+    programs::{MPL_BUBBLEGUM_ID, SPL_ACCOUNT_COMPRESSION_ID},
+};
+use spl_account_compression::ConcurrentMerkleTreeError;
+use num_traits::FromPrimitive;
+use rustler::{types::map::map_new, Atom, Encoder, Env, Term};
+use std::str::FromStr;
+
+// Define atoms for the keys of the error map
+rustler::atoms! {
+    kind,
+    message,
+    details,
+    code,
+    transaction_error,
+    logs,
+    instruction_index,
+    program_id,
+    program_error
+}
+
+// Anchor programs number their custom errors from 6000
+const ANCHOR_ERROR_CODE_OFFSET: u32 = 6000;
+
+// Error of a failed RPC call, returned to Elixir as
+// `%{kind: atom, message: string, details: map}`. When the transaction itself
+// failed, `kind` is the snake_cased TransactionError variant (e.g.
+// `:blockhash_not_found`, `:instruction_error`). Custom errors of Bubblegum
+// and the account compression program are decoded into `program_error` and
+// replace the message with the program's description of the error.
+pub struct ClientErrorNif {
+    pub kind: String,
+    pub message: String,
+    pub code: Option<i64>,
+    pub transaction_error: Option<String>,
+    pub logs: Vec<String>,
+    pub instruction_index: Option<u8>,
+    pub program_id: Option<String>,
+    pub program_error: Option<String>,
+}
+
+impl ClientErrorNif {
+    fn new(kind: &str, message: String) -> Self {
+        ClientErrorNif {
+            kind: kind.to_string(),
+            message,
+            code: None,
+            transaction_error: None,
+            logs: Vec::new(),
+            instruction_index: None,
+            program_id: None,
+            program_error: None,
+        }
+    }
+
+    fn from_transaction_error(err: &TransactionError, message: String, logs: Vec<String>) -> Self {
+        let instruction_index = match err {
+            TransactionError::InstructionError(index, _) => Some(*index),
+            _ => None
+        };
+
+        let program_error = match err {
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                decode_custom_error(*code, &logs)
+            }
+            _ => None
+        };
+
+        let (message, program_id, program_error) = match program_error {
+            Some(decoded) => (decoded.message, Some(decoded.program_id.to_string()), Some(decoded.name)),
+            None => (message, None, None)
+        };
+
+        ClientErrorNif {
+            kind: variant_name(&format!("{:?}", err)),
+            message,
+            code: None,
+            transaction_error: Some(err.to_string()),
+            logs,
+            instruction_index,
+            program_id,
+            program_error,
+        }
+    }
+}
+
+impl From<&ClientError> for ClientErrorNif {
+    fn from(err: &ClientError) -> Self {
+        let message = err.to_string();
+
+        match &err.kind {
+            ClientErrorKind::TransactionError(tx_err) => {
+                ClientErrorNif::from_transaction_error(tx_err, message, Vec::new())
+            }
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, data, .. }) => {
+                // Preflight failures carry the simulated transaction error and logs
+                let mut error = match data {
+                    RpcResponseErrorData::SendTransactionPreflightFailure(simulation) => match &simulation.err {
+                        Some(tx_err) => ClientErrorNif::from_transaction_error(
+                            tx_err,
+                            message.clone(),
+                            simulation.logs.clone().unwrap_or_default()
+                        ),
+                        None => ClientErrorNif::new("rpc_error", message.clone())
+                    },
+                    _ => ClientErrorNif::new("rpc_error", message.clone())
+                };
+                error.code = Some(*code);
+                error
+            }
+            ClientErrorKind::RpcError(_) => ClientErrorNif::new("rpc_error", message),
+            ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => ClientErrorNif::new("network_error", message),
+            ClientErrorKind::SerdeJson(_) => ClientErrorNif::new("serialization_error", message),
+            ClientErrorKind::SigningError(_) => ClientErrorNif::new("signing_error", message),
+            ClientErrorKind::Custom(_) => ClientErrorNif::new("client_error", message),
+        }
+    }
+}
+
+impl Encoder for ClientErrorNif {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let kind_atom = make_atom(env, &self.kind);
+
+        let details_map = map_new(env);
+        let details_map = put(details_map, code(), self.code);
+        let details_map = put(details_map, transaction_error(), &self.transaction_error);
+        let details_map = put(details_map, logs(), &self.logs);
+        let details_map = put(details_map, instruction_index(), self.instruction_index);
+        let details_map = put(details_map, program_id(), &self.program_id);
+        let details_map = match &self.program_error {
+            Some(name) => put(details_map, program_error(), make_atom(env, name)),
+            None => put(details_map, program_error(), None::<Atom>)
+        };
+
+        let error_map = map_new(env);
+        let error_map = put(error_map, kind(), kind_atom);
+        let error_map = put(error_map, message(), &self.message);
+        put(error_map, details(), details_map)
+    }
+}
+
+fn make_atom(env: Env, name: &str) -> Atom {
+    Atom::from_str(env, name).unwrap_or_else(|_| rustler::types::atom::error())
+}
+
+fn put<'a>(map: Term<'a>, key: impl Encoder, value: impl Encoder) -> Term<'a> {
+    map.map_put(key, value).unwrap_or(map)
+}
+
+// "InstructionError(0, Custom(1))" -> "instruction_error"
+fn variant_name(debug: &str) -> String {
+    let mut name = String::new();
+    for c in debug.chars().take_while(|c| c.is_alphanumeric()) {
+        if c.is_uppercase() && !name.is_empty() {
+            name.push('_');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
+
+// Custom program error decoded to its variant name and description
+struct DecodedProgramError {
+    program_id: Pubkey,
+    name: String,
+    message: String,
+}
+
+impl DecodedProgramError {
+    fn new(program_id: Pubkey, variant: &impl std::fmt::Debug, message: String) -> Self {
+        DecodedProgramError {
+            program_id,
+            name: variant_name(&format!("{:?}", variant)),
+            message,
+        }
+    }
+}
+
+// The program that raised the error is the first one logged as failed, since
+// errors of a CPI are logged before the calling program fails with them.
+// None without logs, since the program cannot be told then.
+fn failed_program(logs: &[String]) -> Option<Pubkey> {
+    logs.iter()
+        .find_map(|line| {
+            let program = line.strip_prefix("Program ")?;
+            let (program, _) = program.split_once(" failed: ")?;
+            Pubkey::from_str(program).ok()
+        })
+}
+
+// The account compression program logs the underlying merkle tree error
+// before failing with its generic ConcurrentMerkleTreeError
+fn concurrent_merkle_tree_error(logs: &[String]) -> Option<ConcurrentMerkleTreeError> {
+    let errors = [
+        ConcurrentMerkleTreeError::LeafIndexOutOfBounds,
+        ConcurrentMerkleTreeError::InvalidProof,
+        ConcurrentMerkleTreeError::CannotAppendEmptyNode,
+        ConcurrentMerkleTreeError::TreeFull,
+        ConcurrentMerkleTreeError::TreeAlreadyInitialized,
+        ConcurrentMerkleTreeError::TreeNotInitialized,
+        ConcurrentMerkleTreeError::RootNotFound,
+        ConcurrentMerkleTreeError::LeafContentsModified,
+        ConcurrentMerkleTreeError::TreeNonEmpty,
+    ];
+
+    errors.into_iter().find(|err| {
+        let message = err.to_string();
+        logs.iter().any(|line| line.ends_with(&message))
+    })
+}
+
+fn decode_custom_error(code: u32, logs: &[String]) -> Option<DecodedProgramError> {
+    let index = code.checked_sub(ANCHOR_ERROR_CODE_OFFSET)?;
+    let program_id = failed_program(logs)?;
+
+    if program_id == MPL_BUBBLEGUM_ID {
+        let err = MplBubblegumError::from_u32(index)?;
+        return Some(DecodedProgramError::new(program_id, &err, err.to_string()));
+    }
+
+    if program_id == SPL_ACCOUNT_COMPRESSION_ID {
+        let err = SplAccountCompressionError::from_u32(index)?;
+        if err == SplAccountCompressionError::ConcurrentMerkleTreeError {
+            if let Some(tree_err) = concurrent_merkle_tree_error(logs) {
+                return Some(DecodedProgramError::new(program_id, &tree_err, tree_err.to_string()));
+            }
+        }
+        return Some(DecodedProgramError::new(program_id, &err, err.to_string()));
+    }
+
+    None
+}
+
+// Whether the node rejected a transaction because it does not know its
+// blockhash, either because it expired or because the node is behind
+pub fn is_blockhash_not_found(err: &ClientError) -> bool {
+    let tx_err = match &err.kind {
+        ClientErrorKind::TransactionError(tx_err) => Some(tx_err),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(simulation),
+            ..
+        }) => simulation.err.as_ref(),
+        _ => None
+    };
+    matches!(tx_err, Some(TransactionError::BlockhashNotFound))
+}
+
+// Convert a failed RPC call into a structured error term
+pub fn client_error(err: ClientError) -> rustler::Error {
+    rustler::Error::Term(Box::new(ClientErrorNif::from(&err)))
+}
+
+// Structured error of a transaction that failed in simulation, with its
+// custom program error decoded from the simulation logs
+pub fn simulation_failure(err: &TransactionError, logs: Vec<String>) -> ClientErrorNif {
+    let message = format!("Transaction simulation failed: {}", err);
+    ClientErrorNif::from_transaction_error(err, message, logs)
+}
+
+// Error for a transaction that failed in simulation
+pub fn simulation_error(err: &TransactionError, logs: Vec<String>) -> rustler::Error {
+    rustler::Error::Term(Box::new(simulation_failure(err, logs)))
+}
+
+// Structured error for a sent transaction that failed on chain, with its
+// custom program error decoded from the transaction logs
+pub fn failed_transaction_error(err: &TransactionError, logs: Vec<String>) -> rustler::Error {
+    let message = format!("Transaction failed: {}", err);
+    rustler::Error::Term(Box::new(ClientErrorNif::from_transaction_error(err, message, logs)))
+}
+
+// A sent transaction that was not confirmed before its blockhash expired is
+// reported as `:blockhash_not_found`, like the node does for an expired
+// transaction at preflight
+pub fn expired_error(signature: &Signature) -> rustler::Error {
+    let message = format!("Transaction {} expired before it was confirmed: block height exceeded", signature);
+    let err = TransactionError::BlockhashNotFound;
+    rustler::Error::Term(Box::new(ClientErrorNif::from_transaction_error(&err, message, Vec::new())))
+}
+
+// Structured error for a sent transaction still unconfirmed at the confirm timeout.
+// It may still land, so its signature is part of the message.
+pub fn confirmation_timeout_error(signature: &Signature) -> rustler::Error {
+    let message = format!("Transaction {} was not confirmed within the confirm timeout", signature);
+    rustler::Error::Term(Box::new(ClientErrorNif::new("confirmation_timeout", message)))
+}
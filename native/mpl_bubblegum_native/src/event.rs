@@ -0,0 +1,115 @@
+// File: native/mpl_bubblegum_native/src/event.rs
+
+use mpl_bubblegum::{
+    programs::SPL_NOOP_ID,
+    types::{BubblegumEventType, LeafSchema, Version},
+};
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::commitment_config::CommitmentConfig;
+use borsh::BorshDeserialize;
+use serde_json::{json, Value};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use crate::rpc::{CommitmentNif, RpcClientResource};
+use crate::transaction::CONFIRM_POLL_INTERVAL;
+
+// Bubblegum logs its events through the noop program wrapped in the account
+// compression program's AccountCompressionEvent::ApplicationData, version V1
+const APPLICATION_DATA_EVENT: u8 = 1;
+const APPLICATION_DATA_EVENT_V1: u8 = 0;
+
+// Decode the LeafSchema event from the data of a noop instruction. Other
+// events, such as the change log of the account compression program, give None.
+fn parse_leaf_schema_event(data: &[u8]) -> Option<LeafSchema> {
+    if data.first() != Some(&APPLICATION_DATA_EVENT) {
+        return None;
+    }
+
+    let (_, version, application_data) = <(u8, u8, Vec<u8>)>::deserialize(&mut &data[..]).ok()?;
+    if version != APPLICATION_DATA_EVENT_V1 {
+        return None;
+    }
+
+    // The leaf hash following the schema is not needed
+    let (event_type, _, schema) =
+        <(BubblegumEventType, Version, LeafSchema)>::deserialize(&mut &application_data[..]).ok()?;
+
+    (event_type == BubblegumEventType::LeafSchemaEvent).then_some(schema)
+}
+
+// Account keys of a json encoded transaction, including the addresses loaded
+// from lookup tables, in the order used by `programIdIndex`
+fn account_keys(transaction: &Value) -> Vec<&str> {
+    let loaded_addresses = &transaction["meta"]["loadedAddresses"];
+
+    [
+        &transaction["transaction"]["message"]["accountKeys"],
+        &loaded_addresses["writable"],
+        &loaded_addresses["readonly"],
+    ]
+    .into_iter()
+    .filter_map(Value::as_array)
+    .flatten()
+    .filter_map(Value::as_str)
+    .collect()
+}
+
+// First LeafSchema event among the inner instructions of the transaction
+fn find_leaf_schema_event(transaction: &Value) -> Option<LeafSchema> {
+    let account_keys = account_keys(transaction);
+    let noop_program_id = SPL_NOOP_ID.to_string();
+
+    transaction["meta"]["innerInstructions"]
+        .as_array()?
+        .iter()
+        .filter_map(|inner| inner["instructions"].as_array())
+        .flatten()
+        .filter(|instruction| {
+            let program_id = instruction["programIdIndex"]
+                .as_u64()
+                .and_then(|index| account_keys.get(index as usize));
+            program_id == Some(&noop_program_id.as_str())
+        })
+        .filter_map(|instruction| bs58::decode(instruction["data"].as_str()?).into_vec().ok())
+        .find_map(|data| parse_leaf_schema_event(&data))
+}
+
+// Fetch a confirmed Bubblegum transaction and return the leaf from its
+// LeafSchema event. getTransaction does not serve processed transactions, so
+// it is polled until the transaction is available at `confirmed` at least, for
+// up to the client's confirm timeout. Without one it is requested once, so a
+// `processed` send does not wait for confirmation here. Failed requests are
+// retried the same way. None when the transaction was not returned in time or
+// has no LeafSchema event.
+pub fn fetch_leaf_schema(
+    rpc_client: &RpcClientResource,
+    signature: &str,
+    commitment: CommitmentNif,
+) -> Option<LeafSchema> {
+    let commitment = match commitment {
+        CommitmentNif::Finalized => CommitmentConfig::finalized(),
+        CommitmentNif::Processed | CommitmentNif::Confirmed => CommitmentConfig::confirmed(),
+    };
+    let params = json!([
+        signature,
+        {
+            "encoding": "json",
+            "commitment": commitment.commitment,
+            "maxSupportedTransactionVersion": 0
+        }
+    ]);
+
+    let timeout = rpc_client.confirm_timeout.unwrap_or(Duration::ZERO);
+    let started = Instant::now();
+
+    loop {
+        match rpc_client.send::<Value>(RpcRequest::GetTransaction, params.clone()) {
+            Ok(transaction) if !transaction.is_null() => return find_leaf_schema_event(&transaction),
+            // Not available at the commitment yet, or the request failed
+            _ if started.elapsed() >= timeout => return None,
+            _ => {}
+        }
+
+        sleep(CONFIRM_POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,42 @@
+// File: native/mpl_bubblegum_native/src/instruction.rs
+
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use rustler::{Binary, Env, NewBinary, NifStruct};
+
+// NIF struct to return an account meta to Elixir
+#[derive(NifStruct)]
+#[module = "MplBubblegumEx.AccountMeta"]
+pub struct AccountMetaNif {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// NIF struct to return an instruction to Elixir, `data` is a binary
+#[derive(NifStruct)]
+#[module = "MplBubblegumEx.Instruction"]
+pub struct InstructionNif<'a> {
+    pub program_id: String,
+    pub accounts: Vec<AccountMetaNif>,
+    pub data: Binary<'a>,
+}
+
+fn convert_account_meta(meta: &AccountMeta) -> AccountMetaNif {
+    AccountMetaNif {
+        pubkey: meta.pubkey.to_string(),
+        is_signer: meta.is_signer,
+        is_writable: meta.is_writable,
+    }
+}
+
+// Convert a Solana instruction for returning to Elixir
+pub fn convert_instruction<'a>(env: Env<'a>, instruction: &Instruction) -> InstructionNif<'a> {
+    let mut data = NewBinary::new(env, instruction.data.len());
+    data.as_mut_slice().copy_from_slice(&instruction.data);
+
+    InstructionNif {
+        program_id: instruction.program_id.to_string(),
+        accounts: instruction.accounts.iter().map(convert_account_meta).collect(),
+        data: data.into(),
+    }
+}
@@ -23,7 +23,6 @@
 //     validate_keypair  
 // ]);
 
-use rustler::{Encoder, Env, Error, Term};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use std::str::FromStr;
@@ -33,6 +32,21 @@ mod valid_depth_size_pairs;
 mod metadata;
 mod mint;
 mod transfer;
+mod burn;
+mod delegate;
+mod tree_delegate;
+mod creator;
+mod collection;
+mod update;
+mod decompress;
+mod proof;
+mod transaction;
+mod instruction;
+mod error;
+mod task;
+mod rpc;
+mod event;
+mod asset;
 
 #[rustler::nif]
 pub fn validate_pubkey_nif(pubkey_str: &str) -> bool {
@@ -44,11 +58,45 @@ pub fn validate_keypair_nif(keypair_bytes: Vec<u8>) -> bool {
     keypair_bytes.len() == 64 && Keypair::from_bytes(&keypair_bytes).is_ok()
 }
 
+// NIFs that call the RPC node are scheduled as DirtyIo so that waiting for
+// confirmation does not block the BEAM schedulers. Pure NIFs (validation,
+// build_* and *_ix) stay on the normal schedulers, as do the *_async NIFs
+// which only hand the work to a thread pool. find_asset_nonce derives one
+// PDA per nonce of its range and runs as DirtyCpu.
 rustler::init!("Elixir.MplBubblegumEx.Native", [
     validate_pubkey_nif,
     validate_keypair_nif,
+    rpc::open_rpc_client,
+    asset::get_asset_id,
+    asset::find_asset_nonce,
     create_tree::create_tree_config_tx,
+    create_tree::build_create_tree_config_tx,
+    create_tree::create_tree_config_ix,
+    create_tree::simulate_create_tree_config_tx,
     mint::mint_compressed_nft_tx,
     mint::mint_to_collection_tx,
-    transfer::transfer_compressed_nft
+    mint::mint_compressed_nft_tx_async,
+    mint::mint_to_collection_tx_async,
+    mint::build_mint_compressed_nft_tx,
+    mint::build_mint_to_collection_tx,
+    mint::mint_compressed_nft_ix,
+    mint::mint_to_collection_ix,
+    mint::simulate_mint_compressed_nft_tx,
+    transfer::transfer_compressed_nft,
+    transfer::transfer_compressed_nft_async,
+    transfer::build_transfer_compressed_nft,
+    transfer::transfer_compressed_nft_ix,
+    transfer::simulate_transfer_compressed_nft,
+    burn::burn_compressed_nft,
+    delegate::delegate_compressed_nft,
+    tree_delegate::set_tree_delegate,
+    creator::verify_creator,
+    creator::unverify_creator,
+    collection::verify_collection,
+    collection::unverify_collection,
+    collection::set_and_verify_collection,
+    update::update_metadata,
+    decompress::redeem_compressed_nft,
+    decompress::cancel_redeem_compressed_nft,
+    decompress::decompress_compressed_nft
 ]);
@@ -1,10 +1,13 @@
 use mpl_bubblegum::types::{
-    Creator, MetadataArgs, TokenProgramVersion, TokenStandard, Collection, Uses, UseMethod
+    Creator, MetadataArgs, TokenProgramVersion, TokenStandard, Collection, Uses, UseMethod, UpdateArgs
 };
+use mpl_bubblegum::hash::{hash_creators, hash_metadata};
 use rustler::{NifStruct, NifUnitEnum};
-use solana_program::pubkey::Pubkey;
+use solana_program::{pubkey, pubkey::Pubkey};
 use std::str::FromStr;
 
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
 // NIF struct to receive creator data from Elixir
 #[derive(NifStruct)]
 #[module = "MplBubblegumEx.Creator"]
@@ -65,30 +68,22 @@ pub struct MetadataArgsNif {
     pub creators: Vec<CreatorNif>,
 }
 
+// NIF struct for metadata updates from Elixir, `nil` fields are left unchanged
+#[derive(NifStruct)]
+#[module = "MplBubblegumEx.UpdateArgs"]
+pub struct UpdateArgsNif {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+    pub creators: Option<Vec<CreatorNif>>,
+    pub seller_fee_basis_points: Option<u16>,
+    pub primary_sale_happened: Option<bool>,
+    pub is_mutable: Option<bool>,
+}
+
 // Convert Elixir metadata to mpl-bubblegum MetadataArgs
 pub fn convert_metadata_args(args: &MetadataArgsNif) -> Result<MetadataArgs, String> {
-    // Convert creators
-    let creators: Vec<Creator> = args.creators
-        .iter()
-        .map(|c| {
-            let address = match Pubkey::from_str(&c.address) {
-                Ok(pubkey) => pubkey,
-                Err(_) => return Err(format!("Invalid creator address: {}", c.address)),
-            };
-            
-            Ok(Creator {
-                address,
-                verified: c.verified,
-                share: c.share,
-            })
-        })
-        .collect::<Result<Vec<Creator>, String>>()?;
-    
-    // Validate creator shares
-    let total_shares: u16 = creators.iter().map(|c| c.share as u16).sum();
-    if !creators.is_empty() && total_shares != 100 {
-        return Err(format!("Creator shares must sum to 100, got {}", total_shares));
-    }
+    let creators = convert_creators(&args.creators)?;
     
     // Convert collection if present
     let collection = args.collection.as_ref().map(|c| {
@@ -143,3 +138,73 @@ pub fn convert_metadata_args(args: &MetadataArgsNif) -> Result<MetadataArgs, Str
         creators,
     })
 }
+
+// Convert Elixir creators, checking that their shares sum to 100
+fn convert_creators(creators: &[CreatorNif]) -> Result<Vec<Creator>, String> {
+    let creators: Vec<Creator> = creators
+        .iter()
+        .map(|c| {
+            let address = match Pubkey::from_str(&c.address) {
+                Ok(pubkey) => pubkey,
+                Err(_) => return Err(format!("Invalid creator address: {}", c.address)),
+            };
+            
+            Ok(Creator {
+                address,
+                verified: c.verified,
+                share: c.share,
+            })
+        })
+        .collect::<Result<Vec<Creator>, String>>()?;
+    
+    // Validate creator shares
+    let total_shares: u16 = creators.iter().map(|c| c.share as u16).sum();
+    if !creators.is_empty() && total_shares != 100 {
+        return Err(format!("Creator shares must sum to 100, got {}", total_shares));
+    }
+
+    Ok(creators)
+}
+
+// Convert an Elixir metadata update to mpl-bubblegum UpdateArgs
+pub fn convert_update_args(args: &UpdateArgsNif) -> Result<UpdateArgs, String> {
+    let creators = args.creators
+        .as_deref()
+        .map(convert_creators)
+        .transpose()?;
+
+    Ok(UpdateArgs {
+        name: args.name.clone(),
+        symbol: args.symbol.clone(),
+        uri: args.uri.clone(),
+        creators,
+        seller_fee_basis_points: args.seller_fee_basis_points,
+        primary_sale_happened: args.primary_sale_happened,
+        is_mutable: args.is_mutable,
+    })
+}
+
+// Compute the data hash and creator hash stored in the leaf for this metadata
+pub fn hash_metadata_args(metadata: &MetadataArgs) -> Result<([u8; 32], [u8; 32]), String> {
+    let data_hash = hash_metadata(metadata)
+        .map_err(|e| format!("Failed to hash metadata: {}", e))?;
+    let creator_hash = hash_creators(&metadata.creators);
+
+    Ok((data_hash, creator_hash))
+}
+
+// Token Metadata account of a mint
+pub fn find_metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    ).0
+}
+
+// Token Metadata master edition account of a mint
+pub fn find_master_edition_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref(), b"edition"],
+        &TOKEN_METADATA_PROGRAM_ID,
+    ).0
+}
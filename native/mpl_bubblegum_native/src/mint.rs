@@ -2,24 +2,30 @@
 
 // use solana_program::system_program;
 use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
     pubkey::Pubkey,
     signature::Keypair,
-    transaction::Transaction,
     signer::Signer
 };
 use mpl_bubblegum::{
     instructions::{MintV1Builder, MintToCollectionV1Builder},
-    types::MetadataArgs,
-    ID as BUBBLEGUM_PROGRAM_ID,
-    programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID},
+    types::{LeafSchema, MetadataArgs},
     accounts::TreeConfig,
+    utils::get_asset_id,
 };
-use solana_client::rpc_client::RpcClient;
+use crate::event::fetch_leaf_schema;
+use crate::rpc::{CommitmentNif, RpcClientResource};
 use std::str::FromStr;
-use rustler::{NifStruct, atoms, Binary};
-use crate::metadata::{MetadataArgsNif, convert_metadata_args};
-use bincode::serialize;
-use bs58;
+use rustler::{NifStruct, Binary, Env, Reference, ResourceArc};
+use crate::metadata::{MetadataArgsNif, convert_metadata_args, find_master_edition_pda, find_metadata_pda};
+use crate::instruction::{InstructionNif, convert_instruction};
+use crate::proof::parse_pubkey;
+use crate::task::spawn_task;
+use crate::transaction::{
+    SendOptions, SendResultNif, SimulationResultNif, TxEncoding, build_transaction, encode_transaction, parse_blockhash, parse_signers,
+    send_transaction, simulate_transaction,
+};
 
 // Define atoms for error handling
 rustler::atoms! {
@@ -28,32 +34,210 @@ rustler::atoms! {
     invalid_keypair,
     invalid_pubkey,
     invalid_metadata,
-    rpc_error,
     serialization_error,
     instruction_error
 }
 
-#[rustler::nif]
+// NIF struct to return a confirmed mint with the leaf it appended to the tree.
+// The leaf fields are None when the leaf could not be read after the mint.
+#[derive(NifStruct)]
+#[module = "MplBubblegumEx.MintResult"]
+pub struct MintResultNif {
+    pub signature: String,
+    pub slot: u64,
+    pub commitment: CommitmentNif,
+    pub asset_id: Option<String>,
+    pub leaf_index: Option<u32>,
+    pub nonce: Option<u64>,
+    pub data_hash: Option<String>,
+    pub creator_hash: Option<String>,
+}
+
+// Number of NFTs minted in the tree, from its tree config account. None when
+// the account cannot be read.
+fn num_minted(rpc_client: &RpcClientResource, merkle_tree: &Pubkey, commitment: CommitmentConfig) -> Option<u64> {
+    let (tree_config, _) = TreeConfig::find_pda(merkle_tree);
+    let account = rpc_client.get_account_with_commitment(&tree_config, commitment).ok()?.value?;
+
+    TreeConfig::from_bytes(&account.data).ok().map(|config| config.num_minted)
+}
+
+// Read the minted leaf from the LeafSchema event of the confirmed transaction.
+// Bubblegum appends every minted leaf, so its index in the tree is the nonce.
+// The mint has landed, so this never fails: without the event, the nonce is
+// `num_minted_before` when this mint is the only one since it was read, and
+// the hashes are left empty. When that cannot be told either, every leaf
+// field is left empty.
+fn mint_result(
+    rpc_client: &RpcClientResource,
+    merkle_tree: &Pubkey,
+    num_minted_before: Option<u64>,
+    result: SendResultNif
+) -> MintResultNif {
+    let mut mint_result = MintResultNif {
+        signature: result.signature,
+        slot: result.slot,
+        commitment: result.commitment,
+        asset_id: None,
+        leaf_index: None,
+        nonce: None,
+        data_hash: None,
+        creator_hash: None,
+    };
+
+    match fetch_leaf_schema(rpc_client, &mint_result.signature, result.commitment) {
+        Some(LeafSchema::V1 { id, nonce, data_hash, creator_hash, .. }) => {
+            mint_result.asset_id = Some(id.to_string());
+            mint_result.leaf_index = Some(nonce as u32);
+            mint_result.nonce = Some(nonce);
+            mint_result.data_hash = Some(bs58::encode(data_hash).into_string());
+            mint_result.creator_hash = Some(bs58::encode(creator_hash).into_string());
+        }
+        None => {
+            let num_minted_after = num_minted(rpc_client, merkle_tree, result.commitment.into());
+            if let Some(nonce) = num_minted_before.filter(|before| num_minted_after == Some(before + 1)) {
+                mint_result.asset_id = Some(get_asset_id(merkle_tree, nonce).to_string());
+                mint_result.leaf_index = Some(nonce as u32);
+                mint_result.nonce = Some(nonce);
+            }
+        }
+    }
+
+    mint_result
+}
+
+// MintV1 instruction, with the leaf owner as the initial delegate
+pub fn mint_v1_instruction(
+    merkle_tree: &Pubkey,
+    leaf_owner: &Pubkey,
+    payer: &Pubkey,
+    tree_creator_or_delegate: &Pubkey,
+    metadata: MetadataArgs
+) -> Instruction {
+    let (tree_authority, _) = TreeConfig::find_pda(merkle_tree);
+
+    MintV1Builder::new()
+        .tree_config(tree_authority)
+        .leaf_owner(*leaf_owner)
+        .leaf_delegate(*leaf_owner) // Default to owner as delegate
+        .merkle_tree(*merkle_tree)
+        .payer(*payer)
+        .tree_creator_or_delegate(*tree_creator_or_delegate)
+        .metadata(metadata)
+        .instruction()
+}
+
+// MintToCollectionV1 instruction, with the collection metadata and master
+// edition derived from the collection mint
+pub fn mint_to_collection_instruction(
+    merkle_tree: &Pubkey,
+    leaf_owner: &Pubkey,
+    payer: &Pubkey,
+    tree_creator_or_delegate: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_authority: &Pubkey,
+    collection_authority_record: Option<Pubkey>,
+    metadata: MetadataArgs
+) -> Instruction {
+    let (tree_authority, _) = TreeConfig::find_pda(merkle_tree);
+
+    MintToCollectionV1Builder::new()
+        .tree_config(tree_authority)
+        .leaf_owner(*leaf_owner)
+        .leaf_delegate(*leaf_owner) // Default to owner as delegate
+        .merkle_tree(*merkle_tree)
+        .payer(*payer)
+        .tree_creator_or_delegate(*tree_creator_or_delegate)
+        .collection_authority(*collection_authority)
+        .collection_authority_record_pda(collection_authority_record)
+        .collection_mint(*collection_mint)
+        .collection_metadata(find_metadata_pda(collection_mint))
+        .collection_edition(find_master_edition_pda(collection_mint))
+        .metadata(metadata)
+        .instruction()
+}
+
+#[rustler::nif(schedule = "DirtyIo")]
 pub fn mint_compressed_nft_tx(
     tree_keypair: Binary,  // Changed from tree_pubkey_str
     leaf_owner_pubkey_str: &str, 
     payer_keypair: Binary,
+    tree_authority_keypair: Option<Binary>,
     metadata_args: MetadataArgsNif,
-    rpc_url: &str
-) -> Result<(rustler::Atom, String), rustler::Error> {
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, MintResultNif), rustler::Error> {
+    mint_compressed_nft(
+        tree_keypair.as_slice(),
+        leaf_owner_pubkey_str,
+        payer_keypair.as_slice(),
+        tree_authority_keypair.as_ref().map(|keypair| keypair.as_slice()),
+        &metadata_args,
+        &send_options,
+        &rpc_client
+    )
+}
+
+// Async variant of `mint_compressed_nft_tx`. Returns `{:ok, ref}` right away
+// and sends `{ref, result}` to the caller once the transaction confirms or fails.
+#[rustler::nif]
+pub fn mint_compressed_nft_tx_async<'a>(
+    env: Env<'a>,
+    tree_keypair: Binary,
+    leaf_owner_pubkey_str: String,
+    payer_keypair: Binary,
+    tree_authority_keypair: Option<Binary>,
+    metadata_args: MetadataArgsNif,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, Reference<'a>), rustler::Error> {
+    let tree_keypair = tree_keypair.as_slice().to_vec();
+    let payer_keypair = payer_keypair.as_slice().to_vec();
+    let tree_authority_keypair = tree_authority_keypair.map(|keypair| keypair.as_slice().to_vec());
+
+    let reference = spawn_task(env, move || mint_compressed_nft(
+        &tree_keypair,
+        &leaf_owner_pubkey_str,
+        &payer_keypair,
+        tree_authority_keypair.as_deref(),
+        &metadata_args,
+        &send_options,
+        &rpc_client
+    ))?;
+
+    Ok((ok(), reference))
+}
+
+fn mint_compressed_nft(
+    tree_keypair: &[u8],
+    leaf_owner_pubkey_str: &str,
+    payer_keypair: &[u8],
+    tree_authority_keypair: Option<&[u8]>,
+    metadata_args: &MetadataArgsNif,
+    send_options: &SendOptions,
+    rpc_client: &RpcClientResource
+) -> Result<(rustler::Atom, MintResultNif), rustler::Error> {
     // Parse tree keypair
-    let tree_keypair_vec = tree_keypair.as_slice().to_vec();
-    let merkle_tree = match Keypair::from_bytes(&tree_keypair_vec) {
+    let merkle_tree = match Keypair::from_bytes(tree_keypair) {
         Ok(keypair) => keypair,
         Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
     };
     let merkle_tree_pubkey = merkle_tree.pubkey();
 
-    let payer_vec = payer_keypair.as_slice().to_vec();
-    let payer = match Keypair::from_bytes(&payer_vec) {
+    let payer = match Keypair::from_bytes(payer_keypair) {
         Ok(keypair) => keypair,
         Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
     };
+
+    // Tree creator or tree delegate, defaults to the payer
+    let tree_authority_signer = match tree_authority_keypair {
+        Some(keypair_bytes) => match Keypair::from_bytes(keypair_bytes) {
+            Ok(keypair) => Some(keypair),
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+        },
+        None => None
+    };
+    let tree_authority_signer = tree_authority_signer.as_ref().unwrap_or(&payer);
     
     let leaf_owner = match Pubkey::from_str(leaf_owner_pubkey_str) {
         Ok(pubkey) => pubkey,
@@ -61,73 +245,139 @@ pub fn mint_compressed_nft_tx(
     };
     
     // Convert metadata
-    let metadata = match convert_metadata_args(&metadata_args) {
+    let metadata = match convert_metadata_args(metadata_args) {
         Ok(meta) => meta,
         Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
     };
     
-    // Get PDA for tree_authority
-    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
-    
-    // Setup RPC client
-    let rpc_client = RpcClient::new(rpc_url);
-    let recent_blockhash = match rpc_client.get_latest_blockhash() {
-        Ok(hash) => hash,
-        Err(_) => return Err(rustler::Error::Term(Box::new(rpc_error())))
-    };
-    
     // Create regular mint instruction
-    let mint_ix = MintV1Builder::new()
-        .tree_config(tree_authority)
-        .leaf_owner(leaf_owner)
-        .leaf_delegate(leaf_owner) // Default to owner as delegate
-        .merkle_tree(merkle_tree_pubkey)
-        .payer(payer.pubkey())
-        .tree_creator_or_delegate(payer.pubkey())
-        .metadata(metadata)
-        .instruction();
+    let mint_ix = mint_v1_instruction(
+        &merkle_tree_pubkey,
+        &leaf_owner,
+        &payer.pubkey(),
+        &tree_authority_signer.pubkey(),
+        metadata
+    );
         
-    // Build and sign transaction
-    let transaction = Transaction::new_signed_with_payer(
+    // Tree authority only signs separately when it differs from the payer
+    let mut signers = vec![&payer];
+    if tree_authority_signer.pubkey() != payer.pubkey() {
+        signers.push(tree_authority_signer);
+    }
+
+    // The mint count before sending locates the leaf if its event cannot be read
+    let num_minted_before = num_minted(rpc_client, &merkle_tree_pubkey, rpc_client.commitment());
+
+    // Sign, submit and confirm transaction
+    let result = send_transaction(
+        rpc_client,
         &[mint_ix],
-        Some(&payer.pubkey()),
-        &[&payer],
-        recent_blockhash,
-    );
-    
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)
-    .map_err(|_| rustler::Error::Term(Box::new(rpc_error())))?;
+        &payer.pubkey(),
+        &signers,
+        send_options
+    )?;
 
-    Ok((ok(), signature.to_string()))
+    Ok((ok(), mint_result(rpc_client, &merkle_tree_pubkey, num_minted_before, result)))
 }
 
-#[rustler::nif]
+#[rustler::nif(schedule = "DirtyIo")]
 pub fn mint_to_collection_tx(
     tree_keypair: Binary,
     leaf_owner_pubkey_str: &str,
     payer_keypair: Binary,
+    tree_authority_keypair: Option<Binary>,
     metadata_args: MetadataArgsNif,
     collection_mint_str: &str,
     collection_authority_keypair: Binary,
     collection_authority_record_pda_str: Option<String>,
-    rpc_url: &str
-) -> Result<(rustler::Atom, String), rustler::Error> {
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, MintResultNif), rustler::Error> {
+    mint_to_collection(
+        tree_keypair.as_slice(),
+        leaf_owner_pubkey_str,
+        payer_keypair.as_slice(),
+        tree_authority_keypair.as_ref().map(|keypair| keypair.as_slice()),
+        &metadata_args,
+        collection_mint_str,
+        collection_authority_keypair.as_slice(),
+        collection_authority_record_pda_str.as_deref(),
+        &send_options,
+        &rpc_client
+    )
+}
+
+// Async variant of `mint_to_collection_tx`, see `mint_compressed_nft_tx_async`
+#[rustler::nif]
+pub fn mint_to_collection_tx_async<'a>(
+    env: Env<'a>,
+    tree_keypair: Binary,
+    leaf_owner_pubkey_str: String,
+    payer_keypair: Binary,
+    tree_authority_keypair: Option<Binary>,
+    metadata_args: MetadataArgsNif,
+    collection_mint_str: String,
+    collection_authority_keypair: Binary,
+    collection_authority_record_pda_str: Option<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, Reference<'a>), rustler::Error> {
+    let tree_keypair = tree_keypair.as_slice().to_vec();
+    let payer_keypair = payer_keypair.as_slice().to_vec();
+    let tree_authority_keypair = tree_authority_keypair.map(|keypair| keypair.as_slice().to_vec());
+    let collection_authority_keypair = collection_authority_keypair.as_slice().to_vec();
+
+    let reference = spawn_task(env, move || mint_to_collection(
+        &tree_keypair,
+        &leaf_owner_pubkey_str,
+        &payer_keypair,
+        tree_authority_keypair.as_deref(),
+        &metadata_args,
+        &collection_mint_str,
+        &collection_authority_keypair,
+        collection_authority_record_pda_str.as_deref(),
+        &send_options,
+        &rpc_client
+    ))?;
+
+    Ok((ok(), reference))
+}
+
+fn mint_to_collection(
+    tree_keypair: &[u8],
+    leaf_owner_pubkey_str: &str,
+    payer_keypair: &[u8],
+    tree_authority_keypair: Option<&[u8]>,
+    metadata_args: &MetadataArgsNif,
+    collection_mint_str: &str,
+    collection_authority_keypair: &[u8],
+    collection_authority_record_pda_str: Option<&str>,
+    send_options: &SendOptions,
+    rpc_client: &RpcClientResource
+) -> Result<(rustler::Atom, MintResultNif), rustler::Error> {
     // Parse tree keypair
-    let tree_keypair_vec = tree_keypair.as_slice().to_vec();
-    let merkle_tree = match Keypair::from_bytes(&tree_keypair_vec) {
+    let merkle_tree = match Keypair::from_bytes(tree_keypair) {
         Ok(keypair) => keypair,
         Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
     };
     let merkle_tree_pubkey = merkle_tree.pubkey();
     // Parse inputs
-    let payer_vec = payer_keypair.as_slice().to_vec();
-    let payer = match Keypair::from_bytes(&payer_vec) {
+    let payer = match Keypair::from_bytes(payer_keypair) {
         Ok(keypair) => keypair,
         Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
     };
 
-    let collection_vec = collection_authority_keypair.as_slice().to_vec();
-    let collection_authority = match Keypair::from_bytes(&collection_vec) {
+    // Tree creator or tree delegate, defaults to the payer
+    let tree_authority_signer = match tree_authority_keypair {
+        Some(keypair_bytes) => match Keypair::from_bytes(keypair_bytes) {
+            Ok(keypair) => Some(keypair),
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+        },
+        None => None
+    };
+    let tree_authority_signer = tree_authority_signer.as_ref().unwrap_or(&payer);
+
+    let collection_authority = match Keypair::from_bytes(collection_authority_keypair) {
         Ok(keypair) => keypair,
         Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
     };
@@ -144,7 +394,7 @@ pub fn mint_to_collection_tx(
     
     // Parse optional collection authority record PDA
     let collection_authority_record = if let Some(pda_str) = collection_authority_record_pda_str {
-        match Pubkey::from_str(&pda_str) {
+        match Pubkey::from_str(pda_str) {
             Ok(pubkey) => Some(pubkey),
             Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
         }
@@ -153,53 +403,256 @@ pub fn mint_to_collection_tx(
     };
     
     // Convert metadata
-    let metadata = match convert_metadata_args(&metadata_args) {
+    let metadata = match convert_metadata_args(metadata_args) {
         Ok(meta) => meta,
         Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
     };
     
-    // Get PDA for tree_authority
-    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
-    
-    // Setup RPC client
-    let rpc_client = RpcClient::new(rpc_url);
-    let recent_blockhash = match rpc_client.get_latest_blockhash() {
-        Ok(hash) => hash,
-        Err(_) => return Err(rustler::Error::Term(Box::new(rpc_error())))
-    };
-    
     // Create mint to collection instruction
-    let mint_ix = MintToCollectionV1Builder::new()
-        .tree_config(tree_authority)
-        .leaf_owner(leaf_owner)
-        .leaf_delegate(leaf_owner) // Default to owner as delegate
-        .merkle_tree(merkle_tree_pubkey)
-        .payer(payer.pubkey())
-        .tree_creator_or_delegate(payer.pubkey())
-        .collection_authority(collection_authority.pubkey())
-        .collection_authority_record_pda(collection_authority_record)
-        .collection_mint(collection_mint)
-        .metadata(metadata)
-        .instruction();
+    let mint_ix = mint_to_collection_instruction(
+        &merkle_tree_pubkey,
+        &leaf_owner,
+        &payer.pubkey(),
+        &tree_authority_signer.pubkey(),
+        &collection_mint,
+        &collection_authority.pubkey(),
+        collection_authority_record,
+        metadata
+    );
     
     // Get signers - we need both payer and collection authority
     let mut signers = vec![&payer];
     
-    // Only add collection authority if it's different from payer
-    if collection_authority.pubkey() != payer.pubkey() {
+    // Only add tree authority and collection authority if they're different from payer
+    if tree_authority_signer.pubkey() != payer.pubkey() {
+        signers.push(tree_authority_signer);
+    }
+    if collection_authority.pubkey() != payer.pubkey()
+        && collection_authority.pubkey() != tree_authority_signer.pubkey() {
         signers.push(&collection_authority);
     }
     
-    // Build and sign transaction
-    let transaction = Transaction::new_signed_with_payer(
+    // The mint count before sending locates the leaf if its event cannot be read
+    let num_minted_before = num_minted(rpc_client, &merkle_tree_pubkey, rpc_client.commitment());
+
+    // Sign, submit and confirm transaction
+    let result = send_transaction(
+        rpc_client,
         &[mint_ix],
-        Some(&payer.pubkey()),
+        &payer.pubkey(),
         &signers,
-        recent_blockhash,
+        send_options
+    )?;
+
+    Ok((ok(), mint_result(rpc_client, &merkle_tree_pubkey, num_minted_before, result)))
+}
+
+// Builds the mint transaction without sending it. It is signed by whichever
+// of `signer_keypairs` are required, the other signatures are left empty.
+#[rustler::nif]
+pub fn build_mint_compressed_nft_tx(
+    tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    payer_pubkey_str: &str,
+    tree_authority_pubkey_str: Option<String>,
+    metadata_args: MetadataArgsNif,
+    signer_keypairs: Vec<Binary>,
+    recent_blockhash_str: &str,
+    encoding: TxEncoding
+) -> Result<(rustler::Atom, String), rustler::Error> {
+    let merkle_tree_pubkey = parse_pubkey(tree_pubkey_str)?;
+    let leaf_owner = parse_pubkey(leaf_owner_pubkey_str)?;
+    let payer = parse_pubkey(payer_pubkey_str)?;
+
+    // Tree creator or tree delegate, defaults to the payer
+    let tree_authority = match tree_authority_pubkey_str {
+        Some(pubkey_str) => parse_pubkey(&pubkey_str)?,
+        None => payer
+    };
+
+    let signers = parse_signers(&signer_keypairs)?;
+    let recent_blockhash = parse_blockhash(recent_blockhash_str)?;
+
+    // Convert metadata
+    let metadata = match convert_metadata_args(&metadata_args) {
+        Ok(meta) => meta,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    let mint_ix = mint_v1_instruction(&merkle_tree_pubkey, &leaf_owner, &payer, &tree_authority, metadata);
+    let transaction = build_transaction(&[mint_ix], &payer, &signers, recent_blockhash)?;
+
+    Ok((ok(), encode_transaction(&transaction, encoding)?))
+}
+
+// Simulates the mint transaction instead of submitting it. Takes public keys
+// only, since signatures are not verified during simulation.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn simulate_mint_compressed_nft_tx(
+    tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    payer_pubkey_str: &str,
+    tree_authority_pubkey_str: Option<String>,
+    metadata_args: MetadataArgsNif,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SimulationResultNif), rustler::Error> {
+    let merkle_tree_pubkey = parse_pubkey(tree_pubkey_str)?;
+    let leaf_owner = parse_pubkey(leaf_owner_pubkey_str)?;
+    let payer = parse_pubkey(payer_pubkey_str)?;
+
+    // Tree creator or tree delegate, defaults to the payer
+    let tree_authority = match tree_authority_pubkey_str {
+        Some(pubkey_str) => parse_pubkey(&pubkey_str)?,
+        None => payer
+    };
+
+    // Convert metadata
+    let metadata = match convert_metadata_args(&metadata_args) {
+        Ok(meta) => meta,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    let mint_ix = mint_v1_instruction(&merkle_tree_pubkey, &leaf_owner, &payer, &tree_authority, metadata);
+    let simulation = simulate_transaction(&rpc_client, &[mint_ix], &payer, &send_options)?;
+
+    Ok((ok(), simulation))
+}
+
+// Builds the mint to collection transaction without sending it, see
+// `build_mint_compressed_nft_tx`
+#[rustler::nif]
+pub fn build_mint_to_collection_tx(
+    tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    payer_pubkey_str: &str,
+    tree_authority_pubkey_str: Option<String>,
+    metadata_args: MetadataArgsNif,
+    collection_mint_str: &str,
+    collection_authority_pubkey_str: &str,
+    collection_authority_record_pda_str: Option<String>,
+    signer_keypairs: Vec<Binary>,
+    recent_blockhash_str: &str,
+    encoding: TxEncoding
+) -> Result<(rustler::Atom, String), rustler::Error> {
+    let merkle_tree_pubkey = parse_pubkey(tree_pubkey_str)?;
+    let leaf_owner = parse_pubkey(leaf_owner_pubkey_str)?;
+    let payer = parse_pubkey(payer_pubkey_str)?;
+
+    // Tree creator or tree delegate, defaults to the payer
+    let tree_authority = match tree_authority_pubkey_str {
+        Some(pubkey_str) => parse_pubkey(&pubkey_str)?,
+        None => payer
+    };
+
+    let collection_mint = parse_pubkey(collection_mint_str)?;
+    let collection_authority = parse_pubkey(collection_authority_pubkey_str)?;
+    let collection_authority_record = collection_authority_record_pda_str
+        .as_deref()
+        .map(parse_pubkey)
+        .transpose()?;
+
+    let signers = parse_signers(&signer_keypairs)?;
+    let recent_blockhash = parse_blockhash(recent_blockhash_str)?;
+
+    // Convert metadata
+    let metadata = match convert_metadata_args(&metadata_args) {
+        Ok(meta) => meta,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    let mint_ix = mint_to_collection_instruction(
+        &merkle_tree_pubkey,
+        &leaf_owner,
+        &payer,
+        &tree_authority,
+        &collection_mint,
+        &collection_authority,
+        collection_authority_record,
+        metadata
     );
-    
-    let signature = rpc_client.send_and_confirm_transaction(&transaction)
-    .map_err(|_| rustler::Error::Term(Box::new(rpc_error())))?;
+    let transaction = build_transaction(&[mint_ix], &payer, &signers, recent_blockhash)?;
+
+    Ok((ok(), encode_transaction(&transaction, encoding)?))
+}
+
+// Returns the MintV1 instruction without building a transaction
+#[rustler::nif]
+pub fn mint_compressed_nft_ix<'a>(
+    env: Env<'a>,
+    tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    payer_pubkey_str: &str,
+    tree_authority_pubkey_str: Option<String>,
+    metadata_args: MetadataArgsNif
+) -> Result<(rustler::Atom, InstructionNif<'a>), rustler::Error> {
+    let merkle_tree_pubkey = parse_pubkey(tree_pubkey_str)?;
+    let leaf_owner = parse_pubkey(leaf_owner_pubkey_str)?;
+    let payer = parse_pubkey(payer_pubkey_str)?;
+
+    // Tree creator or tree delegate, defaults to the payer
+    let tree_authority = match tree_authority_pubkey_str {
+        Some(pubkey_str) => parse_pubkey(&pubkey_str)?,
+        None => payer
+    };
+
+    // Convert metadata
+    let metadata = match convert_metadata_args(&metadata_args) {
+        Ok(meta) => meta,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
 
-    Ok((ok(), signature.to_string()))
-}
\ No newline at end of file
+    let mint_ix = mint_v1_instruction(&merkle_tree_pubkey, &leaf_owner, &payer, &tree_authority, metadata);
+
+    Ok((ok(), convert_instruction(env, &mint_ix)))
+}
+
+// Returns the MintToCollectionV1 instruction without building a transaction
+#[rustler::nif]
+pub fn mint_to_collection_ix<'a>(
+    env: Env<'a>,
+    tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    payer_pubkey_str: &str,
+    tree_authority_pubkey_str: Option<String>,
+    metadata_args: MetadataArgsNif,
+    collection_mint_str: &str,
+    collection_authority_pubkey_str: &str,
+    collection_authority_record_pda_str: Option<String>
+) -> Result<(rustler::Atom, InstructionNif<'a>), rustler::Error> {
+    let merkle_tree_pubkey = parse_pubkey(tree_pubkey_str)?;
+    let leaf_owner = parse_pubkey(leaf_owner_pubkey_str)?;
+    let payer = parse_pubkey(payer_pubkey_str)?;
+
+    // Tree creator or tree delegate, defaults to the payer
+    let tree_authority = match tree_authority_pubkey_str {
+        Some(pubkey_str) => parse_pubkey(&pubkey_str)?,
+        None => payer
+    };
+
+    let collection_mint = parse_pubkey(collection_mint_str)?;
+    let collection_authority = parse_pubkey(collection_authority_pubkey_str)?;
+    let collection_authority_record = collection_authority_record_pda_str
+        .as_deref()
+        .map(parse_pubkey)
+        .transpose()?;
+
+    // Convert metadata
+    let metadata = match convert_metadata_args(&metadata_args) {
+        Ok(meta) => meta,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    let mint_ix = mint_to_collection_instruction(
+        &merkle_tree_pubkey,
+        &leaf_owner,
+        &payer,
+        &tree_authority,
+        &collection_mint,
+        &collection_authority,
+        collection_authority_record,
+        metadata
+    );
+
+    Ok((ok(), convert_instruction(env, &mint_ix)))
+}
@@ -0,0 +1,89 @@
+// File: native/mpl_bubblegum_native/src/proof.rs
+
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+use solana_client::rpc_client::RpcClient;
+use mpl_bubblegum::{accounts::MerkleTree, types::ConcurrentMerkleTreeHeaderData};
+use spl_account_compression::state::CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1;
+use std::str::FromStr;
+use crate::error::client_error;
+
+// Define atoms for error handling
+rustler::atoms! {
+    invalid_pubkey,
+    invalid_hash,
+    invalid_proof,
+    invalid_tree_account
+}
+
+// Decode a base58 encoded public key (tree, owner, asset id, ...)
+pub fn parse_pubkey(pubkey_str: &str) -> Result<Pubkey, rustler::Error> {
+    Pubkey::from_str(pubkey_str)
+        .map_err(|_| rustler::Error::Term(Box::new(invalid_pubkey())))
+}
+
+// Decode a base58 encoded 32-byte value (root, data hash or creator hash)
+pub fn parse_hash(hash_str: &str) -> Result<[u8; 32], rustler::Error> {
+    let bytes = match bs58::decode(hash_str).into_vec() {
+        Ok(bytes) => bytes,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_hash())))
+    };
+
+    match <[u8; 32]>::try_from(bytes.as_slice()) {
+        Ok(hash) => Ok(hash),
+        Err(_) => Err(rustler::Error::Term(Box::new(invalid_hash())))
+    }
+}
+
+// Convert the proof path (base58 node hashes, leaf to root) into the
+// read-only remaining accounts expected by the compression program.
+// Nodes already stored in the tree's canopy are dropped from the top.
+pub fn proof_accounts(proof: &[String], canopy_depth: u32) -> Result<Vec<AccountMeta>, rustler::Error> {
+    let needed = proof.len().saturating_sub(canopy_depth as usize);
+
+    proof[..needed]
+        .iter()
+        .map(|node| match Pubkey::from_str(node) {
+            Ok(pubkey) => Ok(AccountMeta::new_readonly(pubkey, false)),
+            Err(_) => Err(rustler::Error::Term(Box::new(invalid_proof())))
+        })
+        .collect()
+}
+
+// Size in bytes of a ConcurrentMerkleTree<max_depth, max_buffer_size>:
+// three u64 counters, the changelog buffer and the rightmost proof
+pub fn merkle_tree_size(max_depth: usize, max_buffer_size: usize) -> usize {
+    let change_log_size = 32 + 32 * max_depth + 8;
+    let rightmost_proof_size = 32 * max_depth + 32 + 8;
+    24 + max_buffer_size * change_log_size + rightmost_proof_size
+}
+
+// Size in bytes of a canopy caching the top `canopy_depth` levels of the tree
+pub fn canopy_size(canopy_depth: u32) -> usize {
+    ((1usize << (canopy_depth + 1)) - 2) * 32
+}
+
+// Read the merkle tree account and derive its canopy depth from the
+// space left after the header and the tree itself
+pub fn fetch_canopy_depth(rpc_client: &RpcClient, merkle_tree: &Pubkey) -> Result<u32, rustler::Error> {
+    let data = rpc_client.get_account_data(merkle_tree)
+        .map_err(client_error)?;
+
+    let tree = match MerkleTree::from_bytes(&data) {
+        Ok(tree) => tree,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_tree_account())))
+    };
+
+    let (max_depth, max_buffer_size) = match tree.tree_header {
+        ConcurrentMerkleTreeHeaderData::V1 { max_depth, max_buffer_size, .. } => {
+            (max_depth as usize, max_buffer_size as usize)
+        }
+    };
+
+    let canopy_bytes = data
+        .len()
+        .checked_sub(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 + merkle_tree_size(max_depth, max_buffer_size))
+        .ok_or_else(|| rustler::Error::Term(Box::new(invalid_tree_account())))?;
+
+    // A canopy of depth d stores 2^(d+1) - 2 nodes
+    Ok((canopy_bytes / 32 + 2).trailing_zeros() - 1)
+}
@@ -0,0 +1,198 @@
+// File: native/mpl_bubblegum_native/src/rpc.rs
+
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_client::{RpcClient, RpcClientConfig},
+    rpc_custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+    rpc_request::{RpcError, RpcRequest},
+};
+use solana_rpc_client::{
+    http_sender::HttpSender,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_sdk::commitment_config::CommitmentConfig;
+use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
+use rustler::{NifStruct, NifUnitEnum, ResourceArc};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+// Define atoms for error handling
+rustler::atoms! {
+    ok,
+    invalid_url
+}
+
+// Same as the default timeout of RpcClient::new
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+
+#[derive(NifUnitEnum, Clone, Copy)]
+pub enum CommitmentNif {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl From<CommitmentNif> for CommitmentConfig {
+    fn from(commitment: CommitmentNif) -> Self {
+        match commitment {
+            CommitmentNif::Processed => CommitmentConfig::processed(),
+            CommitmentNif::Confirmed => CommitmentConfig::confirmed(),
+            CommitmentNif::Finalized => CommitmentConfig::finalized(),
+        }
+    }
+}
+
+// Order in which the endpoints of a client are tried for reads
+#[derive(NifUnitEnum, Clone, Copy)]
+pub enum EndpointPolicy {
+    // Always start with the first endpoint, the others are fallbacks
+    Fallback,
+    // Start with the next endpoint on each request
+    RoundRobin,
+}
+
+#[derive(NifStruct)]
+#[module = "MplBubblegumEx.RpcOptions"]
+pub struct RpcOptions {
+    pub commitment: Option<CommitmentNif>,
+    pub timeout_ms: Option<u64>,
+    pub confirm_timeout_ms: Option<u64>,
+    pub policy: Option<EndpointPolicy>,
+}
+
+// Sender spreading requests over several endpoints. Reads go to one endpoint
+// at a time and move on to the next when it cannot be reached or reports
+// itself unhealthy. Transactions are sent to every endpoint.
+struct MultiEndpointSender {
+    endpoints: Vec<HttpSender>,
+    policy: EndpointPolicy,
+    next: AtomicUsize,
+}
+
+impl MultiEndpointSender {
+    // Endpoint indices in the order they are tried for the next read
+    fn read_order(&self) -> Vec<usize> {
+        let count = self.endpoints.len();
+        let start = match self.policy {
+            EndpointPolicy::Fallback => 0,
+            EndpointPolicy::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed) % count,
+        };
+        (0..count).map(|offset| (start + offset) % count).collect()
+    }
+}
+
+// Errors where another endpoint may still answer the request
+fn is_endpoint_failure(err: &ClientError) -> bool {
+    match &err.kind {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+        }
+        _ => false
+    }
+}
+
+#[async_trait]
+impl RpcSender for MultiEndpointSender {
+    async fn send(&self, request: RpcRequest, params: serde_json::Value) -> ClientResult<serde_json::Value> {
+        if request == RpcRequest::SendTransaction {
+            // Every endpoint gets the transaction and the call waits for all of
+            // them. The first endpoint accepting it wins.
+            let mut broadcasts: FuturesUnordered<_> = self.endpoints.iter()
+                .map(|endpoint| endpoint.send(request, params.clone()))
+                .collect();
+
+            let mut accepted = None;
+            let mut error: Option<ClientError> = None;
+            while let Some(result) = broadcasts.next().await {
+                match result {
+                    Ok(value) => {
+                        accepted.get_or_insert(value);
+                    }
+                    // An error returned by a node, such as a failed preflight,
+                    // is kept over one of an endpoint that could not answer
+                    Err(err) => {
+                        if error.as_ref().is_none_or(is_endpoint_failure) {
+                            error = Some(err);
+                        }
+                    }
+                }
+            }
+            return match accepted {
+                Some(value) => Ok(value),
+                None => Err(error.expect("a client has at least one endpoint")),
+            };
+        }
+
+        let mut last_error = None;
+        for index in self.read_order() {
+            match self.endpoints[index].send(request, params.clone()).await {
+                Err(err) if is_endpoint_failure(&err) => last_error = Some(err),
+                result => return result,
+            }
+        }
+        Err(last_error.expect("a client has at least one endpoint"))
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.endpoints.iter().fold(RpcTransportStats::default(), |mut total, endpoint| {
+            let stats = endpoint.get_transport_stats();
+            total.request_count += stats.request_count;
+            total.elapsed_time += stats.elapsed_time;
+            total.rate_limited_time += stats.rate_limited_time;
+            total
+        })
+    }
+
+    fn url(&self) -> String {
+        self.endpoints[0].url()
+    }
+}
+
+// RPC client shared by every NIF call made with the same handle, so the
+// underlying HTTP connections are reused
+pub struct RpcClientResource {
+    client: RpcClient,
+    // Upper bound on the time a send waits for the requested commitment.
+    // Without it, waiting stops when the blockhash expires.
+    pub confirm_timeout: Option<Duration>,
+}
+
+#[rustler::resource_impl]
+impl rustler::Resource for RpcClientResource {}
+
+impl Deref for RpcClientResource {
+    type Target = RpcClient;
+
+    fn deref(&self) -> &RpcClient {
+        &self.client
+    }
+}
+
+#[rustler::nif]
+pub fn open_rpc_client(
+    urls: Vec<String>,
+    options: RpcOptions
+) -> Result<(rustler::Atom, ResourceArc<RpcClientResource>), rustler::Error> {
+    let valid_url = |url: &String| url.starts_with("http://") || url.starts_with("https://");
+    if urls.is_empty() || !urls.iter().all(valid_url) {
+        return Err(rustler::Error::Term(Box::new(invalid_url())));
+    }
+
+    let commitment = options.commitment
+        .map(CommitmentConfig::from)
+        .unwrap_or_default();
+    let timeout = Duration::from_millis(options.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+    let confirm_timeout = options.confirm_timeout_ms.map(Duration::from_millis);
+
+    let sender = MultiEndpointSender {
+        endpoints: urls.iter().map(|url| HttpSender::new_with_timeout(url, timeout)).collect(),
+        policy: options.policy.unwrap_or(EndpointPolicy::Fallback),
+        next: AtomicUsize::new(0),
+    };
+    let client = RpcClient::new_sender(sender, RpcClientConfig::with_commitment(commitment));
+
+    Ok((ok(), ResourceArc::new(RpcClientResource { client, confirm_timeout })))
+}
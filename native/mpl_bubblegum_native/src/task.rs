@@ -0,0 +1,97 @@
+// File: native/mpl_bubblegum_native/src/task.rs
+
+use rustler::{Atom, Encoder, Env, OwnedEnv, Reference, Term};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex, OnceLock, PoisonError};
+use std::thread;
+
+// Define atoms for error handling
+rustler::atoms! {
+    error,
+    panic,
+    worker_pool_unavailable
+}
+
+// Number of worker threads running async NIFs. Each worker blocks on a single
+// transaction until it confirms, so this bounds how many are in flight.
+const WORKER_COUNT: usize = 32;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+static JOB_SENDER: OnceLock<Mutex<mpsc::Sender<Job>>> = OnceLock::new();
+
+// Workers are started on the first async call
+fn job_sender() -> &'static Mutex<mpsc::Sender<Job>> {
+    JOB_SENDER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for _ in 0..WORKER_COUNT {
+            let receiver = Arc::clone(&receiver);
+            thread::spawn(move || loop {
+                // Jobs catch their own panics, so a poisoned lock still guards
+                // a usable receiver and the pool keeps its size
+                let job = receiver.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                match job {
+                    Ok(job) => job(),
+                    Err(_) => return
+                }
+            });
+        }
+
+        Mutex::new(sender)
+    })
+}
+
+// `{:error, reason}` for an error returned by the work of a task
+fn error_tuple<'a>(env: Env<'a>, err: rustler::Error) -> Term<'a> {
+    let reason = match err {
+        rustler::Error::Term(reason) | rustler::Error::RaiseTerm(reason) => reason.encode(env),
+        rustler::Error::Atom(name) | rustler::Error::RaiseAtom(name) => {
+            Atom::from_str(env, name).unwrap_or_else(|_| error()).encode(env)
+        }
+        rustler::Error::BadArg => rustler::types::atom::badarg().encode(env)
+    };
+    (error(), reason).encode(env)
+}
+
+// Runs `work` on the worker pool and sends `{ref, result}` to the calling
+// process once it finishes, where `result` is `{:ok, value}` or
+// `{:error, reason}` like the result of the blocking NIF. A panic in `work`
+// is sent as `{:error, :panic}` and leaves the worker running. Returns `ref`,
+// or `:worker_pool_unavailable` when the work cannot be queued, in which case
+// no message is sent.
+pub fn spawn_task<'a, F, T>(env: Env<'a>, work: F) -> Result<Reference<'a>, rustler::Error>
+where
+    F: FnOnce() -> Result<T, rustler::Error> + Send + 'static,
+    T: Encoder,
+{
+    let pid = env.pid();
+    let reference = env.make_ref();
+
+    let mut owned_env = OwnedEnv::new();
+    let saved_reference = owned_env.save(reference.encode(env));
+
+    let job: Job = Box::new(move || {
+        let result = catch_unwind(AssertUnwindSafe(work));
+        // The caller may have exited in the meantime, the result is dropped then
+        let _ = owned_env.send_and_clear(&pid, |env| {
+            let result = match result {
+                Ok(Ok(value)) => value.encode(env),
+                Ok(Err(err)) => error_tuple(env, err),
+                Err(_) => (error(), panic()).encode(env)
+            };
+            (saved_reference.load(env), result).encode(env)
+        });
+    });
+
+    let queued = match job_sender().lock() {
+        Ok(sender) => sender.send(job).is_ok(),
+        Err(_) => false
+    };
+    if !queued {
+        return Err(rustler::Error::Term(Box::new(worker_pool_unavailable())));
+    }
+
+    Ok(reference)
+}
@@ -0,0 +1,462 @@
+// File: native/mpl_bubblegum_native/src/transaction.rs
+
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    transaction::Transaction,
+};
+use solana_client::{
+    client_error::ClientError,
+    rpc_client::RpcClient,
+    rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
+    rpc_request::RpcRequest,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use bincode::serialize;
+use serde_json::{json, Value};
+use rustler::{Binary, NifStruct, NifUnitEnum, NifUntaggedEnum};
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use crate::error::{
+    client_error, confirmation_timeout_error, expired_error, failed_transaction_error, is_blockhash_not_found,
+    simulation_error, simulation_failure, ClientErrorNif,
+};
+use crate::rpc::{CommitmentNif, RpcClientResource};
+
+// Define atoms for error handling
+rustler::atoms! {
+    auto,
+    invalid_blockhash,
+    invalid_compute_unit_limit,
+    invalid_keypair,
+    invalid_signer,
+    serialization_error,
+    simulation_failed
+}
+
+// Compute unit limit used while simulating in auto mode
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+// Extra compute units added to the simulated usage in auto mode, in percent
+const DEFAULT_COMPUTE_UNIT_MARGIN: u32 = 10;
+
+// Delay between signature status checks while waiting for confirmation
+pub const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Delay between rebroadcasts of a transaction not yet seen by the cluster
+const DEFAULT_REBROADCAST_INTERVAL_MS: u64 = 2_000;
+
+// Delay before the first re-sign of an expired transaction, doubled after each
+const DEFAULT_RESIGN_BACKOFF_MS: u64 = 500;
+
+// Either a fixed number of compute units or the `:auto` atom
+#[derive(NifUntaggedEnum)]
+pub enum ComputeUnitLimit {
+    Units(u32),
+    Auto(rustler::Atom),
+}
+
+// NIF struct for the options of every NIF sending a transaction
+#[derive(NifStruct)]
+#[module = "MplBubblegumEx.SendOptions"]
+pub struct SendOptions {
+    pub compute_unit_limit: Option<ComputeUnitLimit>,
+    pub compute_unit_price_micro_lamports: Option<u64>,
+    pub compute_unit_margin: Option<u32>,
+    pub commitment: Option<CommitmentNif>,
+    pub skip_preflight: Option<bool>,
+    pub preflight_commitment: Option<CommitmentNif>,
+    pub max_retries: Option<usize>,
+    pub rebroadcast_interval_ms: Option<u64>,
+    pub max_resign_attempts: Option<u32>,
+    pub resign_backoff_ms: Option<u64>,
+}
+
+// NIF struct to return a sent transaction with the slot it landed in and the
+// commitment it had reached when confirmation returned
+#[derive(NifStruct)]
+#[module = "MplBubblegumEx.SendResult"]
+pub struct SendResultNif {
+    pub signature: String,
+    pub slot: u64,
+    pub commitment: CommitmentNif,
+}
+
+// NIF struct to return the outcome of a simulated transaction. `error` is
+// nil when the transaction would have succeeded, otherwise it is the same
+// structured error a failed send returns. Only ever returned to Elixir.
+#[derive(NifStruct)]
+#[module = "MplBubblegumEx.SimulationResult"]
+#[rustler(encode)]
+pub struct SimulationResultNif {
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    pub error: Option<ClientErrorNif>,
+}
+
+// Encoding of transactions returned by the build_* NIFs
+#[derive(NifUnitEnum)]
+pub enum TxEncoding {
+    Base58,
+    Base64,
+}
+
+pub fn parse_blockhash(blockhash_str: &str) -> Result<Hash, rustler::Error> {
+    Hash::from_str(blockhash_str)
+        .map_err(|_| rustler::Error::Term(Box::new(invalid_blockhash())))
+}
+
+pub fn parse_signers(signer_keypairs: &[Binary]) -> Result<Vec<Keypair>, rustler::Error> {
+    signer_keypairs
+        .iter()
+        .map(|keypair_bin| Keypair::from_bytes(keypair_bin.as_slice())
+            .map_err(|_| rustler::Error::Term(Box::new(invalid_keypair()))))
+        .collect()
+}
+
+// Build a transaction and sign it with whichever of the required signers are
+// given. Signatures of the missing signers are left empty for the caller to
+// fill in, so an empty `signers` list yields an unsigned transaction.
+pub fn build_transaction(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &[Keypair],
+    recent_blockhash: Hash,
+) -> Result<Transaction, rustler::Error> {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(payer));
+    transaction.message.recent_blockhash = recent_blockhash;
+
+    let signer_refs: Vec<&Keypair> = signers.iter().collect();
+    transaction
+        .try_partial_sign(&signer_refs, recent_blockhash)
+        .map_err(|_| rustler::Error::Term(Box::new(invalid_signer())))?;
+
+    Ok(transaction)
+}
+
+// Serialize a transaction in the wire format expected by `sendTransaction`
+pub fn encode_transaction(transaction: &Transaction, encoding: TxEncoding) -> Result<String, rustler::Error> {
+    let bytes = serialize(transaction)
+        .map_err(|_| rustler::Error::Term(Box::new(serialization_error())))?;
+
+    Ok(match encoding {
+        TxEncoding::Base58 => bs58::encode(bytes).into_string(),
+        TxEncoding::Base64 => BASE64.encode(bytes),
+    })
+}
+
+fn compute_unit_limit_instruction(units: u32) -> Instruction {
+    ComputeBudgetInstruction::set_compute_unit_limit(units)
+}
+
+// Simulate the transaction with the maximum compute unit limit and return
+// the units it consumed plus the margin
+fn estimate_compute_units(
+    rpc_client: &RpcClient,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &[&Keypair],
+    recent_blockhash: Hash,
+    margin: u32,
+) -> Result<u32, rustler::Error> {
+    let mut simulated_instructions = vec![compute_unit_limit_instruction(MAX_COMPUTE_UNIT_LIMIT)];
+    simulated_instructions.extend_from_slice(instructions);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &simulated_instructions,
+        Some(payer),
+        signers,
+        recent_blockhash,
+    );
+
+    let simulation = rpc_client.simulate_transaction(&transaction)
+        .map_err(client_error)?
+        .value;
+
+    if let Some(err) = simulation.err {
+        return Err(simulation_error(&err, simulation.logs.unwrap_or_default()));
+    }
+
+    let units_consumed = simulation.units_consumed
+        .ok_or_else(|| rustler::Error::Term(Box::new(simulation_failed())))?;
+
+    let units = units_consumed.saturating_mul(100 + margin as u64) / 100;
+    Ok(units.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32)
+}
+
+// Compute unit price and fixed compute unit limit instructions requested in
+// `options`. An `:auto` limit is left to the caller.
+fn compute_budget_instructions(options: &SendOptions) -> Result<Vec<Instruction>, rustler::Error> {
+    let mut instructions = Vec::new();
+    if let Some(price) = options.compute_unit_price_micro_lamports {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+
+    match &options.compute_unit_limit {
+        Some(ComputeUnitLimit::Units(units)) => {
+            instructions.push(compute_unit_limit_instruction(*units));
+        }
+        Some(ComputeUnitLimit::Auto(atom)) if *atom != auto() => {
+            return Err(rustler::Error::Term(Box::new(invalid_compute_unit_limit())));
+        }
+        _ => {}
+    }
+
+    Ok(instructions)
+}
+
+fn is_auto_compute_unit_limit(options: &SendOptions) -> bool {
+    matches!(options.compute_unit_limit, Some(ComputeUnitLimit::Auto(_)))
+}
+
+// Why sending a signed transaction failed. An expired transaction can no
+// longer land, so it is safe to sign it again with a fresh blockhash.
+enum SendAttemptError {
+    Expired(rustler::Error),
+    Failed(rustler::Error),
+}
+
+impl From<SendAttemptError> for rustler::Error {
+    fn from(err: SendAttemptError) -> Self {
+        match err {
+            SendAttemptError::Expired(err) | SendAttemptError::Failed(err) => err,
+        }
+    }
+}
+
+fn failed(err: ClientError) -> SendAttemptError {
+    SendAttemptError::Failed(client_error(err))
+}
+
+// Log messages of a sent transaction, used to tell which program raised its
+// error. getTransaction does not serve processed transactions, so it is polled
+// until the transaction is confirmed or the `deadline` passes. Without a
+// deadline it is requested once. Empty when the logs could not be fetched.
+fn fetch_transaction_logs(rpc_client: &RpcClientResource, signature: &Signature, deadline: Option<Instant>) -> Vec<String> {
+    let params = json!([
+        signature.to_string(),
+        {
+            "encoding": "json",
+            "commitment": CommitmentConfig::confirmed().commitment,
+            "maxSupportedTransactionVersion": 0
+        }
+    ]);
+
+    loop {
+        match rpc_client.send::<Value>(RpcRequest::GetTransaction, params.clone()) {
+            Ok(transaction) if !transaction.is_null() => {
+                return transaction["meta"]["logMessages"]
+                    .as_array()
+                    .map(|logs| logs.iter().filter_map(Value::as_str).map(String::from).collect())
+                    .unwrap_or_default();
+            }
+            // Not confirmed yet, or the request failed
+            _ if deadline.is_none_or(|deadline| Instant::now() >= deadline) => return Vec::new(),
+            _ => {}
+        }
+
+        sleep(CONFIRM_POLL_INTERVAL);
+    }
+}
+
+// Send a signed transaction and poll its status until it reaches `commitment`,
+// fails, expires once the block height passes `last_valid_block_height`, or
+// the `deadline` set by the client's confirm timeout passes.
+// While it is not seen by the cluster, the same transaction is rebroadcast
+// every `rebroadcast_interval_ms`.
+fn send_signed_transaction(
+    rpc_client: &RpcClientResource,
+    transaction: &Transaction,
+    options: &SendOptions,
+    last_valid_block_height: u64,
+    deadline: Option<Instant>,
+) -> Result<SendResultNif, SendAttemptError> {
+    let commitment = options.commitment
+        .map(CommitmentConfig::from)
+        .unwrap_or_else(|| rpc_client.commitment());
+    let preflight_commitment = options.preflight_commitment
+        .map(CommitmentConfig::from)
+        .unwrap_or_else(|| rpc_client.commitment());
+    let rebroadcast_interval = Duration::from_millis(
+        options.rebroadcast_interval_ms.unwrap_or(DEFAULT_REBROADCAST_INTERVAL_MS)
+    );
+
+    let config = RpcSendTransactionConfig {
+        skip_preflight: options.skip_preflight.unwrap_or(false),
+        preflight_commitment: Some(preflight_commitment.commitment),
+        max_retries: options.max_retries,
+        ..RpcSendTransactionConfig::default()
+    };
+
+    let signature = match rpc_client.send_transaction_with_config(transaction, config) {
+        Ok(signature) => signature,
+        Err(err) if is_blockhash_not_found(&err) => return Err(SendAttemptError::Expired(client_error(err))),
+        Err(err) => return Err(failed(err)),
+    };
+
+    // Rebroadcasts skip preflight, which would reject an already processed transaction
+    let rebroadcast_config = RpcSendTransactionConfig {
+        skip_preflight: true,
+        ..config
+    };
+
+    let mut last_broadcast = Instant::now();
+
+    loop {
+        let status = rpc_client.get_signature_statuses(&[signature])
+            .map_err(failed)?
+            .value
+            .pop()
+            .flatten();
+
+        if let Some(status) = status {
+            if let Some(err) = &status.err {
+                let logs = fetch_transaction_logs(rpc_client, &signature, deadline);
+                return Err(SendAttemptError::Failed(failed_transaction_error(err, logs)));
+            }
+            if status.satisfies_commitment(commitment) {
+                // Report the highest level reached, which can exceed the requested one
+                let reached = if status.satisfies_commitment(CommitmentConfig::finalized()) {
+                    CommitmentNif::Finalized
+                } else if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                    CommitmentNif::Confirmed
+                } else {
+                    CommitmentNif::Processed
+                };
+
+                return Ok(SendResultNif {
+                    signature: signature.to_string(),
+                    slot: status.slot,
+                    commitment: reached,
+                });
+            }
+        } else {
+            // Only a transaction the cluster has not seen can still expire
+            let block_height = rpc_client.get_block_height()
+                .map_err(failed)?;
+            if block_height > last_valid_block_height {
+                return Err(SendAttemptError::Expired(expired_error(&signature)));
+            }
+
+            if last_broadcast.elapsed() >= rebroadcast_interval {
+                // A failed rebroadcast is not fatal, the status check decides
+                let _ = rpc_client.send_transaction_with_config(transaction, rebroadcast_config);
+                last_broadcast = Instant::now();
+            }
+        }
+
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(SendAttemptError::Failed(confirmation_timeout_error(&signature)));
+        }
+
+        sleep(CONFIRM_POLL_INTERVAL);
+    }
+}
+
+// Sign, send and confirm a transaction, prepending the compute budget
+// instructions requested in `options`. Confirmation waits for the commitment
+// in `options`, or the client's commitment when none is given. When the
+// blockhash expires before the transaction lands, it is signed again with a
+// fresh blockhash up to `max_resign_attempts` times, waiting
+// `resign_backoff_ms` before the first attempt and twice as long before each
+// following one. The client's confirm timeout bounds the whole send, re-signed
+// attempts included.
+pub fn send_transaction(
+    rpc_client: &RpcClientResource,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &[&Keypair],
+    options: &SendOptions,
+) -> Result<SendResultNif, rustler::Error> {
+    let max_resign_attempts = options.max_resign_attempts.unwrap_or(0);
+    let mut backoff = Duration::from_millis(options.resign_backoff_ms.unwrap_or(DEFAULT_RESIGN_BACKOFF_MS));
+    let mut attempt = 0;
+    let deadline = rpc_client.confirm_timeout.map(|timeout| Instant::now() + timeout);
+
+    loop {
+        let (recent_blockhash, last_valid_block_height) = rpc_client
+            .get_latest_blockhash_with_commitment(rpc_client.commitment())
+            .map_err(client_error)?;
+
+        let mut budget_instructions = compute_budget_instructions(options)?;
+
+        if is_auto_compute_unit_limit(options) {
+            let margin = options.compute_unit_margin.unwrap_or(DEFAULT_COMPUTE_UNIT_MARGIN);
+            let mut priced_instructions = budget_instructions.clone();
+            priced_instructions.extend_from_slice(instructions);
+            let units = estimate_compute_units(
+                rpc_client,
+                &priced_instructions,
+                payer,
+                signers,
+                recent_blockhash,
+                margin
+            )?;
+            budget_instructions.push(compute_unit_limit_instruction(units));
+        }
+
+        budget_instructions.extend_from_slice(instructions);
+
+        let transaction = Transaction::new_signed_with_payer(
+            &budget_instructions,
+            Some(payer),
+            signers,
+            recent_blockhash,
+        );
+
+        match send_signed_transaction(rpc_client, &transaction, options, last_valid_block_height, deadline) {
+            // A new attempt is only made when its backoff ends before the deadline
+            Err(SendAttemptError::Expired(_))
+                if attempt < max_resign_attempts
+                    && deadline.is_none_or(|deadline| Instant::now() + backoff < deadline) => {
+                attempt += 1;
+                sleep(backoff);
+                backoff = backoff.saturating_mul(2);
+            }
+            result => return result.map_err(rustler::Error::from),
+        }
+    }
+}
+
+// Simulate a transaction without signing or submitting it. Signature checks
+// are skipped and the node fills in a recent blockhash, so no keypairs are
+// needed. With an `:auto` limit the transaction is simulated at the maximum
+// limit and the result reports the units it would consume.
+pub fn simulate_transaction(
+    rpc_client: &RpcClient,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    options: &SendOptions,
+) -> Result<SimulationResultNif, rustler::Error> {
+    let mut budget_instructions = compute_budget_instructions(options)?;
+    if is_auto_compute_unit_limit(options) {
+        budget_instructions.push(compute_unit_limit_instruction(MAX_COMPUTE_UNIT_LIMIT));
+    }
+    budget_instructions.extend_from_slice(instructions);
+
+    let transaction = Transaction::new_with_payer(&budget_instructions, Some(payer));
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        ..RpcSimulateTransactionConfig::default()
+    };
+
+    let simulation = rpc_client.simulate_transaction_with_config(&transaction, config)
+        .map_err(client_error)?
+        .value;
+
+    let logs = simulation.logs.unwrap_or_default();
+    let error = simulation.err.map(|err| simulation_failure(&err, logs.clone()));
+
+    Ok(SimulationResultNif {
+        logs,
+        units_consumed: simulation.units_consumed,
+        error,
+    })
+}
@@ -1,20 +1,24 @@
-use solana_program::system_program;
 use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
-    transaction::Transaction,
 };
 use mpl_bubblegum::{
     instructions::TransferBuilder,
-    ID as BUBBLEGUM_PROGRAM_ID,
     accounts::TreeConfig,
+    utils::get_asset_id,
 };
-use solana_client::rpc_client::RpcClient;
+use crate::rpc::RpcClientResource;
 use std::str::FromStr;
-use rustler::{atoms, Binary};
-use bincode::serialize;
-use bs58;
+use rustler::{Binary, Env, Reference, ResourceArc};
+use crate::proof::{fetch_canopy_depth, parse_hash, parse_pubkey, proof_accounts};
+use crate::instruction::{InstructionNif, convert_instruction};
+use crate::task::spawn_task;
+use crate::transaction::{
+    SendOptions, SendResultNif, SimulationResultNif, TxEncoding, build_transaction, encode_transaction, parse_blockhash, parse_signers,
+    send_transaction, simulate_transaction,
+};
 
 // Define atoms for error handling
 rustler::atoms! {
@@ -22,15 +26,49 @@ rustler::atoms! {
     error,
     invalid_keypair,
     invalid_pubkey,
-    rpc_error,
+    invalid_signer,
     serialization_error,
-    instruction_error
+    missing_leaf_fields,
+    asset_id_mismatch
 }
 
-#[rustler::nif]
+// Transfer instruction signed by either the leaf owner or the leaf delegate
+pub fn transfer_instruction(
+    merkle_tree: &Pubkey,
+    leaf_owner: &Pubkey,
+    leaf_delegate: &Pubkey,
+    owner_signs: bool,
+    new_leaf_owner: &Pubkey,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    proof_path: &[AccountMeta]
+) -> Instruction {
+    let (tree_authority, _) = TreeConfig::find_pda(merkle_tree);
+
+    TransferBuilder::new()
+        .tree_config(tree_authority)
+        .leaf_owner(*leaf_owner, owner_signs)
+        .leaf_delegate(*leaf_delegate, !owner_signs)
+        .new_leaf_owner(*new_leaf_owner)
+        .merkle_tree(*merkle_tree)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .add_remaining_accounts(proof_path)
+        .instruction()
+}
+
+#[rustler::nif(schedule = "DirtyIo")]
 pub fn transfer_compressed_nft(
-    tree_keypair: Binary,
-    leaf_owner_keypair: Binary,
+    tree: Binary,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    signer_keypair: Binary,
     new_leaf_owner_pubkey_str: &str,
     asset_id: &str,
     root_str: Option<String>,
@@ -38,126 +76,367 @@ pub fn transfer_compressed_nft(
     creator_hash_str: Option<String>,
     nonce: Option<u64>,
     index: Option<u32>,
-    rpc_url: &str
-) -> Result<(rustler::Atom, String), rustler::Error> {
-    // Parse tree keypair
-    let tree_keypair_vec = tree_keypair.as_slice().to_vec();
-    let merkle_tree = match Keypair::from_bytes(&tree_keypair_vec) {
-        Ok(keypair) => keypair,
-        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
-    };
-    let merkle_tree_pubkey = merkle_tree.pubkey();
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    transfer(
+        tree.as_slice(),
+        leaf_owner_pubkey_str,
+        leaf_delegate_pubkey_str.as_deref(),
+        signer_keypair.as_slice(),
+        new_leaf_owner_pubkey_str,
+        asset_id,
+        root_str.as_deref(),
+        data_hash_str.as_deref(),
+        creator_hash_str.as_deref(),
+        nonce,
+        index,
+        &proof,
+        &send_options,
+        &rpc_client
+    )
+}
+
+// Async variant of `transfer_compressed_nft`. Returns `{:ok, ref}` right away
+// and sends `{ref, result}` to the caller once the transaction confirms or fails.
+#[rustler::nif]
+pub fn transfer_compressed_nft_async<'a>(
+    env: Env<'a>,
+    tree: Binary,
+    leaf_owner_pubkey_str: String,
+    leaf_delegate_pubkey_str: Option<String>,
+    signer_keypair: Binary,
+    new_leaf_owner_pubkey_str: String,
+    asset_id: String,
+    root_str: Option<String>,
+    data_hash_str: Option<String>,
+    creator_hash_str: Option<String>,
+    nonce: Option<u64>,
+    index: Option<u32>,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, Reference<'a>), rustler::Error> {
+    let tree = tree.as_slice().to_vec();
+    let signer_keypair = signer_keypair.as_slice().to_vec();
+
+    let reference = spawn_task(env, move || transfer(
+        &tree,
+        &leaf_owner_pubkey_str,
+        leaf_delegate_pubkey_str.as_deref(),
+        &signer_keypair,
+        &new_leaf_owner_pubkey_str,
+        &asset_id,
+        root_str.as_deref(),
+        data_hash_str.as_deref(),
+        creator_hash_str.as_deref(),
+        nonce,
+        index,
+        &proof,
+        &send_options,
+        &rpc_client
+    ))?;
+
+    Ok((ok(), reference))
+}
+
+// The tree only has to be identified, so either its keypair or its base58
+// public key is accepted
+fn parse_tree_pubkey(tree: &[u8]) -> Result<Pubkey, rustler::Error> {
+    if tree.len() == 64 {
+        return Keypair::from_bytes(tree)
+            .map(|keypair| keypair.pubkey())
+            .map_err(|_| rustler::Error::Term(Box::new(invalid_keypair())));
+    }
+
+    match std::str::from_utf8(tree) {
+        Ok(tree_pubkey_str) => parse_pubkey(tree_pubkey_str),
+        Err(_) => Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    }
+}
 
-    // Parse leaf owner keypair (current owner)
-    let leaf_owner_vec = leaf_owner_keypair.as_slice().to_vec();
-    let leaf_owner = match Keypair::from_bytes(&leaf_owner_vec) {
+fn transfer(
+    tree: &[u8],
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<&str>,
+    signer_keypair: &[u8],
+    new_leaf_owner_pubkey_str: &str,
+    asset_id: &str,
+    root_str: Option<&str>,
+    data_hash_str: Option<&str>,
+    creator_hash_str: Option<&str>,
+    nonce: Option<u64>,
+    index: Option<u32>,
+    proof: &[String],
+    send_options: &SendOptions,
+    rpc_client: &RpcClientResource
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = parse_tree_pubkey(tree)?;
+
+    // Parse signer keypair, either the leaf owner or the leaf delegate
+    let signer = match Keypair::from_bytes(signer_keypair) {
         Ok(keypair) => keypair,
         Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
     };
 
-    // Parse new owner pubkey
-    let new_leaf_owner = match Pubkey::from_str(new_leaf_owner_pubkey_str) {
+    // Parse current owner pubkey
+    let leaf_owner = match Pubkey::from_str(leaf_owner_pubkey_str) {
         Ok(pubkey) => pubkey,
         Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
     };
 
-    // Parse optional parameters
-    let root = if let Some(root_str) = root_str {
-        match <[u8; 32]>::try_from(bs58::decode(root_str).into_vec().map_err(|_| 
-            rustler::Error::Term(Box::new(invalid_pubkey())))?.as_slice()) {
-            Ok(bytes) => Some(bytes),
+    // Parse current delegate pubkey, defaults to the owner when none has been set
+    let leaf_delegate = match leaf_delegate_pubkey_str {
+        Some(delegate_str) => match Pubkey::from_str(delegate_str) {
+            Ok(pubkey) => pubkey,
             Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
-        }
-    } else {
-        None
+        },
+        None => leaf_owner
     };
 
-    let data_hash = if let Some(data_hash_str) = data_hash_str {
-        match <[u8; 32]>::try_from(bs58::decode(data_hash_str).into_vec().map_err(|_| 
-            rustler::Error::Term(Box::new(invalid_pubkey())))?.as_slice()) {
-            Ok(bytes) => Some(bytes),
-            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
-        }
-    } else {
-        None
-    };
+    let owner_signs = signer.pubkey() == leaf_owner;
+    let delegate_signs = !owner_signs && signer.pubkey() == leaf_delegate;
+    if !owner_signs && !delegate_signs {
+        return Err(rustler::Error::Term(Box::new(invalid_signer())));
+    }
 
-    let creator_hash = if let Some(creator_hash_str) = creator_hash_str {
-        match <[u8; 32]>::try_from(bs58::decode(creator_hash_str).into_vec().map_err(|_| 
-            rustler::Error::Term(Box::new(invalid_pubkey())))?.as_slice()) {
-            Ok(bytes) => Some(bytes),
-            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
-        }
-    } else {
-        None
+    // Parse new owner pubkey
+    let new_leaf_owner = match Pubkey::from_str(new_leaf_owner_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
     };
 
+    // Parse optional parameters
+    let root = root_str.map(parse_hash).transpose()?;
+    let data_hash = data_hash_str.map(parse_hash).transpose()?;
+    let creator_hash = creator_hash_str.map(parse_hash).transpose()?;
+
     // Parse asset ID
     let asset_id = match Pubkey::from_str(asset_id) {
         Ok(pubkey) => pubkey,
         Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
     };
 
-    // Get PDA for tree_authority
-    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
-
-    // Setup RPC client
-    let rpc_client = RpcClient::new(rpc_url);
-    let recent_blockhash = match rpc_client.get_latest_blockhash() {
-        Ok(hash) => hash,
-        Err(_) => return Err(rustler::Error::Term(Box::new(rpc_error())))
+    // The leaf hash can only be rebuilt when every leaf field is known
+    let (root, data_hash, creator_hash, nonce, index) = match (root, data_hash, creator_hash, nonce, index) {
+        (Some(root), Some(data_hash), Some(creator_hash), Some(nonce), Some(index)) => {
+            (root, data_hash, creator_hash, nonce, index)
+        }
+        _ => return Err(rustler::Error::Term(Box::new(missing_leaf_fields())))
     };
 
-    // Build transfer instruction
-    let mut transfer_builder = TransferBuilder::new();
-    transfer_builder = transfer_builder
-        .tree_config(tree_authority)
-        .leaf_owner(leaf_owner.pubkey())
-        .leaf_delegate(leaf_owner.pubkey()) // Usually delegate is same as owner
-        .new_leaf_owner(new_leaf_owner)
-        .merkle_tree(merkle_tree_pubkey);
-
-    // Add optional parameters if provided
-    if let Some(nonce_val) = nonce {
-        transfer_builder = transfer_builder.nonce(nonce_val);
+    // Catch a nonce that does not belong to the asset before sending
+    if get_asset_id(&merkle_tree_pubkey, nonce) != asset_id {
+        return Err(rustler::Error::Term(Box::new(asset_id_mismatch())));
     }
 
-    if let Some(index_val) = index {
-        transfer_builder = transfer_builder.index(index_val);
-    }
+    // Only the part of the proof not covered by the canopy is sent
+    let canopy_depth = fetch_canopy_depth(rpc_client, &merkle_tree_pubkey)?;
+    let proof_path = proof_accounts(proof, canopy_depth)?;
 
-    if let Some(root_val) = root {
-        transfer_builder = transfer_builder.root(root_val);
-    }
+    // Build transfer instruction
+    let transfer_ix = transfer_instruction(
+        &merkle_tree_pubkey,
+        &leaf_owner,
+        &leaf_delegate,
+        owner_signs,
+        &new_leaf_owner,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        &proof_path
+    );
 
-    if let Some(data_hash_val) = data_hash {
-        transfer_builder = transfer_builder.data_hash(data_hash_val);
-    }
+    // Sign, submit and confirm transaction, the signer pays the fees
+    let result = send_transaction(
+        rpc_client,
+        &[transfer_ix],
+        &signer.pubkey(),
+        &[&signer],
+        send_options
+    )?;
+
+    Ok((ok(), result))
+}
 
-    if let Some(creator_hash_val) = creator_hash {
-        transfer_builder = transfer_builder.creator_hash(creator_hash_val);
+// Builds the transfer transaction without sending it. `signer_pubkey_str`
+// is the owner or delegate authorizing the transfer and paying the fees.
+// Since no RPC call is made, the tree's canopy depth has to be passed in.
+#[rustler::nif]
+pub fn build_transfer_compressed_nft(
+    tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    signer_pubkey_str: &str,
+    new_leaf_owner_pubkey_str: &str,
+    root_str: &str,
+    data_hash_str: &str,
+    creator_hash_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    canopy_depth: u32,
+    signer_keypairs: Vec<Binary>,
+    recent_blockhash_str: &str,
+    encoding: TxEncoding
+) -> Result<(rustler::Atom, String), rustler::Error> {
+    let merkle_tree_pubkey = parse_pubkey(tree_pubkey_str)?;
+    let leaf_owner = parse_pubkey(leaf_owner_pubkey_str)?;
+
+    // Delegate defaults to the owner when none has been set
+    let leaf_delegate = match leaf_delegate_pubkey_str {
+        Some(delegate_str) => parse_pubkey(&delegate_str)?,
+        None => leaf_owner
+    };
+
+    let signer = parse_pubkey(signer_pubkey_str)?;
+    let owner_signs = signer == leaf_owner;
+    if !owner_signs && signer != leaf_delegate {
+        return Err(rustler::Error::Term(Box::new(invalid_signer())));
     }
 
-    // Build the instruction
-    let transfer_ix = match transfer_builder.instruction() {
-        Ok(ix) => ix,
-        Err(_) => return Err(rustler::Error::Term(Box::new(instruction_error())))
+    let new_leaf_owner = parse_pubkey(new_leaf_owner_pubkey_str)?;
+    let root = parse_hash(root_str)?;
+    let data_hash = parse_hash(data_hash_str)?;
+    let creator_hash = parse_hash(creator_hash_str)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    let signers = parse_signers(&signer_keypairs)?;
+    let recent_blockhash = parse_blockhash(recent_blockhash_str)?;
+
+    let transfer_ix = transfer_instruction(
+        &merkle_tree_pubkey,
+        &leaf_owner,
+        &leaf_delegate,
+        owner_signs,
+        &new_leaf_owner,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        &proof_path
+    );
+    let transaction = build_transaction(&[transfer_ix], &signer, &signers, recent_blockhash)?;
+
+    Ok((ok(), encode_transaction(&transaction, encoding)?))
+}
+
+// Simulates the transfer transaction instead of submitting it. Takes public
+// keys only, since signatures are not verified during simulation. The proof
+// is trimmed to the tree's canopy like in `transfer_compressed_nft`.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn simulate_transfer_compressed_nft(
+    tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    signer_pubkey_str: &str,
+    new_leaf_owner_pubkey_str: &str,
+    root_str: &str,
+    data_hash_str: &str,
+    creator_hash_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SimulationResultNif), rustler::Error> {
+    let merkle_tree_pubkey = parse_pubkey(tree_pubkey_str)?;
+    let leaf_owner = parse_pubkey(leaf_owner_pubkey_str)?;
+
+    // Delegate defaults to the owner when none has been set
+    let leaf_delegate = match leaf_delegate_pubkey_str {
+        Some(delegate_str) => parse_pubkey(&delegate_str)?,
+        None => leaf_owner
     };
 
-    // Create and sign transaction
-    let transaction = Transaction::new_signed_with_payer(
-        &[transfer_ix],
-        Some(&leaf_owner.pubkey()),
-        &[&leaf_owner],
-        recent_blockhash,
+    let signer = parse_pubkey(signer_pubkey_str)?;
+    let owner_signs = signer == leaf_owner;
+    if !owner_signs && signer != leaf_delegate {
+        return Err(rustler::Error::Term(Box::new(invalid_signer())));
+    }
+
+    let new_leaf_owner = parse_pubkey(new_leaf_owner_pubkey_str)?;
+    let root = parse_hash(root_str)?;
+    let data_hash = parse_hash(data_hash_str)?;
+    let creator_hash = parse_hash(creator_hash_str)?;
+
+    let canopy_depth = fetch_canopy_depth(&rpc_client, &merkle_tree_pubkey)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    let transfer_ix = transfer_instruction(
+        &merkle_tree_pubkey,
+        &leaf_owner,
+        &leaf_delegate,
+        owner_signs,
+        &new_leaf_owner,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        &proof_path
     );
+    let simulation = simulate_transaction(&rpc_client, &[transfer_ix], &signer, &send_options)?;
+
+    Ok((ok(), simulation))
+}
+
+// Returns the transfer instruction without building a transaction, see
+// `build_transfer_compressed_nft`
+#[rustler::nif]
+pub fn transfer_compressed_nft_ix<'a>(
+    env: Env<'a>,
+    tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    signer_pubkey_str: &str,
+    new_leaf_owner_pubkey_str: &str,
+    root_str: &str,
+    data_hash_str: &str,
+    creator_hash_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    canopy_depth: u32
+) -> Result<(rustler::Atom, InstructionNif<'a>), rustler::Error> {
+    let merkle_tree_pubkey = parse_pubkey(tree_pubkey_str)?;
+    let leaf_owner = parse_pubkey(leaf_owner_pubkey_str)?;
 
-    // Submit and confirm transaction
-    let signature = match rpc_client.send_and_confirm_transaction(&transaction) {
-        Ok(sig) => sig,
-        Err(_) => return Err(rustler::Error::Term(Box::new(rpc_error())))
+    // Delegate defaults to the owner when none has been set
+    let leaf_delegate = match leaf_delegate_pubkey_str {
+        Some(delegate_str) => parse_pubkey(&delegate_str)?,
+        None => leaf_owner
     };
 
-    // Return signature
-    Ok((ok(), signature.to_string()))
+    let signer = parse_pubkey(signer_pubkey_str)?;
+    let owner_signs = signer == leaf_owner;
+    if !owner_signs && signer != leaf_delegate {
+        return Err(rustler::Error::Term(Box::new(invalid_signer())));
+    }
+
+    let new_leaf_owner = parse_pubkey(new_leaf_owner_pubkey_str)?;
+    let root = parse_hash(root_str)?;
+    let data_hash = parse_hash(data_hash_str)?;
+    let creator_hash = parse_hash(creator_hash_str)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    let transfer_ix = transfer_instruction(
+        &merkle_tree_pubkey,
+        &leaf_owner,
+        &leaf_delegate,
+        owner_signs,
+        &new_leaf_owner,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+        &proof_path
+    );
+
+    Ok((ok(), convert_instruction(env, &transfer_ix)))
 }
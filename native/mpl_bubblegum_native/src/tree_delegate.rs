@@ -0,0 +1,70 @@
+// File: native/mpl_bubblegum_native/src/tree_delegate.rs
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use mpl_bubblegum::{
+    instructions::SetTreeDelegateBuilder,
+    accounts::TreeConfig,
+};
+use crate::rpc::RpcClientResource;
+use std::str::FromStr;
+use rustler::{Binary, ResourceArc};
+use crate::transaction::{SendOptions, SendResultNif, send_transaction};
+
+// Define atoms for error handling
+rustler::atoms! {
+    ok,
+    error,
+    invalid_keypair,
+    invalid_pubkey
+}
+
+// Lets `new_tree_delegate` mint to the tree in place of the tree creator
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn set_tree_delegate(
+    merkle_tree_pubkey_str: &str,
+    tree_creator_keypair: Binary,
+    new_tree_delegate_pubkey_str: &str,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = match Pubkey::from_str(merkle_tree_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    let tree_creator_vec = tree_creator_keypair.as_slice().to_vec();
+    let tree_creator = match Keypair::from_bytes(&tree_creator_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    let new_tree_delegate = match Pubkey::from_str(new_tree_delegate_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    // Get PDA for tree_authority
+    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
+
+    let set_delegate_ix = SetTreeDelegateBuilder::new()
+        .tree_config(tree_authority)
+        .tree_creator(tree_creator.pubkey())
+        .new_tree_delegate(new_tree_delegate)
+        .merkle_tree(merkle_tree_pubkey)
+        .instruction();
+
+    // Tree creator signs and pays
+    let result = send_transaction(
+        &rpc_client,
+        &[set_delegate_ix],
+        &tree_creator.pubkey(),
+        &[&tree_creator],
+        &send_options
+    )?;
+
+    Ok((ok(), result))
+}
@@ -0,0 +1,157 @@
+// File: native/mpl_bubblegum_native/src/update.rs
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use mpl_bubblegum::{
+    instructions::UpdateMetadataBuilder,
+    accounts::TreeConfig,
+};
+use crate::rpc::RpcClientResource;
+use std::str::FromStr;
+use rustler::{Binary, ResourceArc};
+use crate::metadata::{
+    MetadataArgsNif, UpdateArgsNif, TOKEN_METADATA_PROGRAM_ID, convert_metadata_args, convert_update_args, find_metadata_pda
+};
+use crate::proof::{fetch_canopy_depth, parse_hash, proof_accounts};
+use crate::transaction::{SendOptions, SendResultNif, send_transaction};
+
+// Define atoms for error handling
+rustler::atoms! {
+    ok,
+    error,
+    invalid_keypair,
+    invalid_pubkey
+}
+
+// Updates the metadata of a leaf. `authority_keypair` is the collection
+// authority when the NFT is in a verified collection, otherwise the tree
+// creator or tree delegate. It defaults to the payer.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn update_metadata(
+    merkle_tree_pubkey_str: &str,
+    leaf_owner_pubkey_str: &str,
+    leaf_delegate_pubkey_str: Option<String>,
+    payer_keypair: Binary,
+    authority_keypair: Option<Binary>,
+    current_metadata: MetadataArgsNif,
+    update_args: UpdateArgsNif,
+    collection_authority_record_pda_str: Option<String>,
+    root_str: &str,
+    nonce: u64,
+    index: u32,
+    proof: Vec<String>,
+    send_options: SendOptions,
+    rpc_client: ResourceArc<RpcClientResource>
+) -> Result<(rustler::Atom, SendResultNif), rustler::Error> {
+    let merkle_tree_pubkey = match Pubkey::from_str(merkle_tree_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    let payer_vec = payer_keypair.as_slice().to_vec();
+    let payer = match Keypair::from_bytes(&payer_vec) {
+        Ok(keypair) => keypair,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+    };
+
+    let authority_signer = match authority_keypair {
+        Some(keypair_bin) => match Keypair::from_bytes(keypair_bin.as_slice()) {
+            Ok(keypair) => Some(keypair),
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_keypair())))
+        },
+        None => None
+    };
+    let authority_signer = authority_signer.as_ref().unwrap_or(&payer);
+
+    let leaf_owner = match Pubkey::from_str(leaf_owner_pubkey_str) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+    };
+
+    // Delegate defaults to the owner when none has been set
+    let leaf_delegate = match leaf_delegate_pubkey_str {
+        Some(delegate_str) => match Pubkey::from_str(&delegate_str) {
+            Ok(pubkey) => pubkey,
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+        },
+        None => leaf_owner
+    };
+
+    let collection_authority_record = match collection_authority_record_pda_str {
+        Some(pda_str) => match Pubkey::from_str(&pda_str) {
+            Ok(pubkey) => Some(pubkey),
+            Err(_) => return Err(rustler::Error::Term(Box::new(invalid_pubkey())))
+        },
+        None => None
+    };
+
+    // Convert metadata
+    let current_metadata = match convert_metadata_args(&current_metadata) {
+        Ok(meta) => meta,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata: {}", e))))
+    };
+
+    let update_args = match convert_update_args(&update_args) {
+        Ok(args) => args,
+        Err(e) => return Err(rustler::Error::Term(Box::new(format!("Invalid metadata update: {}", e))))
+    };
+
+    let root = parse_hash(root_str)?;
+
+    // Collection accounts are only passed for NFTs in a verified collection
+    let verified_collection = current_metadata.collection
+        .as_ref()
+        .filter(|collection| collection.verified)
+        .map(|collection| collection.key);
+
+    // Get PDA for tree_authority
+    let (tree_authority, _) = TreeConfig::find_pda(&merkle_tree_pubkey);
+
+    // Only the part of the proof not covered by the canopy is sent
+    let canopy_depth = fetch_canopy_depth(&rpc_client, &merkle_tree_pubkey)?;
+    let proof_path = proof_accounts(&proof, canopy_depth)?;
+
+    let mut update_builder = UpdateMetadataBuilder::new();
+    update_builder
+        .tree_config(tree_authority)
+        .authority(authority_signer.pubkey())
+        .leaf_owner(leaf_owner)
+        .leaf_delegate(leaf_delegate)
+        .payer(payer.pubkey())
+        .merkle_tree(merkle_tree_pubkey)
+        .root(root)
+        .nonce(nonce)
+        .index(index)
+        .current_metadata(current_metadata)
+        .update_args(update_args)
+        .add_remaining_accounts(&proof_path);
+
+    if let Some(collection_mint) = verified_collection {
+        update_builder
+            .collection_mint(Some(collection_mint))
+            .collection_metadata(Some(find_metadata_pda(&collection_mint)))
+            .collection_authority_record_pda(collection_authority_record)
+            .token_metadata_program(TOKEN_METADATA_PROGRAM_ID);
+    }
+
+    let update_ix = update_builder.instruction();
+
+    // Get signers - payer and the authority if it is a different key
+    let mut signers = vec![&payer];
+    if authority_signer.pubkey() != payer.pubkey() {
+        signers.push(authority_signer);
+    }
+
+    let result = send_transaction(
+        &rpc_client,
+        &[update_ix],
+        &payer.pubkey(),
+        &signers,
+        &send_options
+    )?;
+
+    Ok((ok(), result))
+}
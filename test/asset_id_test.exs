@@ -0,0 +1,49 @@
+# File: test/asset_id_test.exs
+defmodule MplBubblegumEx.AssetIdTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.Solana
+  import TestHelpers
+
+  @tree "7vTyC2twqbv5ZM5JhNz2U3RzYtTzRNXeSD5CpQxUx3ov"
+
+  # Asset ids of the tree above, derived with mpl_bubblegum::utils::get_asset_id
+  @asset_ids %{
+    0 => "4YDyJNevzbHJKmvVzvKwrnKdDFWzVppCfja2rWTyMLUW",
+    1 => "J26ZjZKRPvdvdHxGvNZwG76NHv43RrTDPgbFivrkCFRM",
+    7 => "AqjZToeZ3zTqPrtEhYC6wCLPTs183oPYkpxcbkreK3jM",
+    1000 => "Gbbt3spNFDVRQuQZHNaoHtUyywrJKHvyQrg1kjb2dC6F"
+  }
+
+  test "derives the asset id of a tree and nonce" do
+    for {nonce, asset_id} <- @asset_ids do
+      assert {:ok, ^asset_id} = NFT.get_asset_id(@tree, nonce)
+    end
+  end
+
+  test "rejects an invalid tree pubkey" do
+    assert {:error, _} = NFT.get_asset_id("not a pubkey", 0)
+  end
+
+  test "finds the nonce of an asset id within a range" do
+    assert {:ok, 7} = NFT.find_asset_nonce(@asset_ids[7], @tree, 0..10)
+    assert {:ok, 1000} = NFT.find_asset_nonce(@asset_ids[1000], @tree, 1000..1000)
+  end
+
+  test "an asset id outside the range or of another tree is not found" do
+    assert {:error, :not_found} = NFT.find_asset_nonce(@asset_ids[1000], @tree, 0..99)
+
+    other_tree = Solana.pubkey_from_keypair(load_test_merkle_keypair())
+    assert {:error, :not_found} = NFT.find_asset_nonce(@asset_ids[0], other_tree, 0..10)
+  end
+
+  test "ranges of more than 2^20 nonces are rejected" do
+    assert {:error, :invalid_nonce_range} = NFT.find_asset_nonce(@asset_ids[0], @tree, 0..1_048_576)
+    assert {:error, :invalid_nonce_range} = NFT.find_asset_nonce(@asset_ids[0], @tree, 0..18_446_744_073_709_551_615)
+  end
+
+  test "round trips between nonce and asset id" do
+    {:ok, asset_id} = NFT.get_asset_id(@tree, 42)
+    assert {:ok, 42} = NFT.find_asset_nonce(asset_id, @tree, 0..100)
+  end
+end
@@ -0,0 +1,61 @@
+# File: test/async_test.exs
+defmodule MplBubblegumEx.AsyncTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  import TestHelpers
+
+  # Nothing listens on port 1, so every RPC call fails before reaching a node
+  @unreachable_url "http://127.0.0.1:1"
+
+  setup do
+    owner = test_pubkey()
+
+    metadata = MetadataArgs.new(
+      "Async Test NFT",
+      "https://example.com/nft-metadata.json",
+      [%Creator{address: owner, verified: false, share: 100}]
+    )
+
+    {:ok, %{owner: owner, metadata: metadata}}
+  end
+
+  test "async mint returns a ref and sends the result to the caller", %{owner: owner, metadata: metadata} do
+    assert {:ok, ref} = NFT.mint_async(
+      load_test_merkle_keypair(),
+      owner,
+      load_test_keypair(),
+      metadata,
+      @unreachable_url
+    )
+
+    assert is_reference(ref)
+    assert_receive {^ref, {:error, %{kind: :network_error}}}, 10_000
+  end
+
+  test "concurrent async mints each get their own result", %{owner: owner, metadata: metadata} do
+    refs =
+      for _ <- 1..5 do
+        {:ok, ref} = NFT.mint_async(
+          load_test_merkle_keypair(),
+          owner,
+          load_test_keypair(),
+          metadata,
+          @unreachable_url
+        )
+        ref
+      end
+
+    assert length(Enum.uniq(refs)) == 5
+
+    for ref <- refs do
+      assert_receive {^ref, {:error, %{kind: :network_error}}}, 10_000
+    end
+  end
+
+  test "async mint with invalid arguments fails without a message", %{owner: owner, metadata: metadata} do
+    assert {:error, _} = NFT.mint_async(<<1, 2, 3>>, owner, load_test_keypair(), metadata, @unreachable_url)
+    refute_receive {_, {:error, _}}, 100
+  end
+end
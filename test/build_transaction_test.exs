@@ -0,0 +1,64 @@
+# File: test/build_transaction_test.exs
+defmodule MplBubblegumEx.BuildTransactionTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.Solana
+  import TestHelpers
+
+  # Building transactions makes no RPC calls, so any valid hash works as the blockhash
+  @blockhash "DtjqUcS2m7TQNR1J8rdBMv7UfUsXBedw9191rHjuEJba"
+  @empty_signature <<0::size(512)>>
+
+  setup do
+    payer_keypair = load_test_keypair()
+    tree_pubkey = Solana.pubkey_from_keypair(load_test_merkle_keypair())
+    owner_pubkey = test_pubkey()
+
+    metadata = MetadataArgs.new(
+      "Test NFT",
+      "https://example.com/nft-metadata.json",
+      [%Creator{address: owner_pubkey, verified: false, share: 100}]
+    )
+
+    {:ok, %{
+      payer_keypair: payer_keypair,
+      tree_pubkey: tree_pubkey,
+      owner_pubkey: owner_pubkey,
+      metadata: metadata
+    }}
+  end
+
+  test "unsigned mint transaction leaves the payer signature empty", %{tree_pubkey: tree, owner_pubkey: owner, metadata: metadata} do
+    {:ok, tx} = NFT.build_mint(tree, owner, owner, metadata, @blockhash, %{encoding: :base64})
+
+    assert <<1, signature::binary-size(64), _message::binary>> = Base.decode64!(tx)
+    assert signature == @empty_signature
+  end
+
+  test "mint transaction is signed by the given keypairs", %{payer_keypair: payer, tree_pubkey: tree, owner_pubkey: owner, metadata: metadata} do
+    {:ok, tx} = NFT.build_mint(tree, owner, owner, metadata, @blockhash, %{signers: [payer], encoding: :base64})
+
+    assert <<1, signature::binary-size(64), _message::binary>> = Base.decode64!(tx)
+    assert signature != @empty_signature
+  end
+
+  test "keypairs that are not required signers are rejected", %{tree_pubkey: tree, owner_pubkey: owner, metadata: metadata} do
+    other_keypair = load_test_merkle_keypair()
+
+    assert {:error, :invalid_signer} =
+      NFT.build_mint(tree, owner, owner, metadata, @blockhash, %{signers: [other_keypair]})
+  end
+
+  test "transfer transaction can be built as base58", %{tree_pubkey: tree, owner_pubkey: owner} do
+    leaf = %{root: @blockhash, data_hash: @blockhash, creator_hash: @blockhash, nonce: 0, index: 0}
+    proof = [tree, tree, tree]
+
+    {:ok, tx} = NFT.build_transfer(tree, owner, owner, tree, leaf, proof, 0, @blockhash)
+
+    assert is_binary(tx)
+    assert {:error, "Invalid encoding: :hex"} =
+      NFT.build_transfer(tree, owner, owner, tree, leaf, proof, 0, @blockhash, %{encoding: :hex})
+  end
+end
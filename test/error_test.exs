@@ -0,0 +1,77 @@
+# File: test/error_test.exs
+defmodule MplBubblegumEx.ErrorTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.Solana
+  import TestHelpers
+
+  @bubblegum_program_id "BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY"
+  @custom_error %{"InstructionError" => [0, %{"Custom" => 6001}]}
+
+  # Nothing listens on port 1, so every RPC call fails before reaching a node
+  @unreachable_url "http://127.0.0.1:1"
+
+  defp metadata(owner) do
+    MetadataArgs.new(
+      "Test NFT",
+      "https://example.com/nft-metadata.json",
+      [%Creator{address: owner, verified: false, share: 100}]
+    )
+  end
+
+  # Server where every sent transaction fails on chain with a custom error of
+  # instruction 0, and getTransaction returns it with `logs`
+  defp failing_transaction_server(logs) do
+    StubRpcServer.start(fn
+      "getSignatureStatuses", _params ->
+        StubRpcServer.context([%{StubRpcServer.finalized_status() | "err" => @custom_error}])
+
+      "getTransaction", [signature | _] when is_list(logs) ->
+        transaction = StubRpcServer.transaction(signature, [])
+        %{transaction | "meta" => Map.merge(transaction["meta"], %{"err" => @custom_error, "logMessages" => logs})}
+
+      "getTransaction", _params ->
+        nil
+
+      method, params ->
+        StubRpcServer.default(method, params)
+    end)
+  end
+
+  defp mint(server) do
+    owner = test_pubkey()
+    NFT.mint(load_test_merkle_keypair(), owner, load_test_keypair(), metadata(owner), server.url)
+  end
+
+  test "unreachable RPC returns a structured network error" do
+    tree = Solana.pubkey_from_keypair(load_test_merkle_keypair())
+    owner = test_pubkey()
+    metadata = metadata(owner)
+
+    assert {:error, %{kind: :network_error, message: message, details: details}} =
+      NFT.simulate_mint(tree, owner, owner, metadata, @unreachable_url)
+
+    assert is_binary(message)
+    assert %{code: nil, transaction_error: nil, logs: [], instruction_index: nil} = details
+  end
+
+  test "a custom program error of a sent transaction is decoded from its logs" do
+    server = failing_transaction_server([
+      "Program #{@bubblegum_program_id} invoke [1]",
+      "Program #{@bubblegum_program_id} failed: custom program error: 0x1771"
+    ])
+
+    assert {:error, %{kind: :instruction_error, message: "PublicKeyMismatch", details: details}} = mint(server)
+    assert %{instruction_index: 0, program_id: @bubblegum_program_id, program_error: :public_key_mismatch} = details
+    assert StubRpcServer.count(server, "getTransaction") == 1
+  end
+
+  test "a custom program error is left undecoded when the logs cannot be fetched" do
+    server = failing_transaction_server(nil)
+
+    assert {:error, %{kind: :instruction_error, details: details}} = mint(server)
+    assert %{instruction_index: 0, program_id: nil, program_error: nil} = details
+  end
+end
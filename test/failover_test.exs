@@ -0,0 +1,120 @@
+# File: test/failover_test.exs
+defmodule MplBubblegumEx.FailoverTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.RpcClient
+  alias MplBubblegumEx.RpcOptions
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.MintResult
+  import TestHelpers
+
+  # Nothing listens on port 1, so requests to it fail to connect
+  @unreachable_url "http://127.0.0.1:1"
+
+  setup do
+    owner = test_pubkey()
+
+    metadata = MetadataArgs.new(
+      "Failover Test NFT",
+      "https://example.com/nft-metadata.json",
+      [%Creator{address: owner, verified: false, share: 100}]
+    )
+
+    {:ok, %{owner: owner, metadata: metadata}}
+  end
+
+  defp mint(owner, metadata, client) do
+    NFT.mint(load_test_merkle_keypair(), owner, load_test_keypair(), metadata, client)
+  end
+
+  defp open(urls, policy) do
+    {:ok, client} = RpcClient.open(urls, %RpcOptions{policy: policy, timeout_ms: 1_000})
+    client
+  end
+
+  test "reads fail over from an unreachable endpoint", %{owner: owner, metadata: metadata} do
+    server = StubRpcServer.start()
+    client = open([@unreachable_url, server.url], :fallback)
+
+    assert {:ok, %MintResult{commitment: :finalized}} = mint(owner, metadata, client)
+    assert StubRpcServer.count(server, "getLatestBlockhash") == 1
+  end
+
+  test "reads fail over from an unhealthy endpoint", %{owner: owner, metadata: metadata} do
+    unhealthy = StubRpcServer.start(fn
+      "getLatestBlockhash", _params -> {:error, %{"code" => -32005, "message" => "Node is unhealthy"}}
+      method, params -> StubRpcServer.default(method, params)
+    end)
+    healthy = StubRpcServer.start()
+    client = open([unhealthy.url, healthy.url], :fallback)
+
+    assert {:ok, %MintResult{}} = mint(owner, metadata, client)
+    assert StubRpcServer.count(unhealthy, "getLatestBlockhash") == 1
+    assert StubRpcServer.count(healthy, "getLatestBlockhash") == 1
+  end
+
+  test "other RPC errors are returned without failing over", %{owner: owner, metadata: metadata} do
+    failing = StubRpcServer.start(fn
+      "getLatestBlockhash", _params -> {:error, %{"code" => -32602, "message" => "Invalid params"}}
+      method, params -> StubRpcServer.default(method, params)
+    end)
+    healthy = StubRpcServer.start()
+    client = open([failing.url, healthy.url], :fallback)
+
+    assert {:error, _} = mint(owner, metadata, client)
+    assert StubRpcServer.count(healthy, "getLatestBlockhash") == 0
+  end
+
+  test "a transaction is sent to every endpoint", %{owner: owner, metadata: metadata} do
+    primary = StubRpcServer.start()
+    backup = StubRpcServer.start()
+    client = open([primary.url, backup.url], :fallback)
+
+    assert {:ok, %MintResult{}} = mint(owner, metadata, client)
+    assert StubRpcServer.count(primary, "sendTransaction") == 1
+    assert StubRpcServer.count(backup, "sendTransaction") == 1
+    # With the fallback policy, reads only reach the primary while it is up
+    assert StubRpcServer.count(primary, "getLatestBlockhash") == 1
+    assert StubRpcServer.count(backup, "getLatestBlockhash") == 0
+  end
+
+  test "a send failing on every endpoint returns the node's error", %{owner: owner, metadata: metadata} do
+    rejecting = StubRpcServer.start(fn
+      "sendTransaction", _params ->
+        {:error, %{
+          "code" => -32002,
+          "message" => "Transaction simulation failed: Attempt to debit an account but found no record of a prior credit.",
+          "data" => %{"err" => "InsufficientFundsForFee", "logs" => [], "accounts" => nil, "unitsConsumed" => 0, "returnData" => nil}
+        }}
+
+      method, params ->
+        StubRpcServer.default(method, params)
+    end)
+    client = open([@unreachable_url, rejecting.url], :fallback)
+
+    assert {:error, %{kind: :insufficient_funds_for_fee}} = mint(owner, metadata, client)
+  end
+
+  test "a send succeeds while one endpoint is unreachable", %{owner: owner, metadata: metadata} do
+    server = StubRpcServer.start()
+    client = open([server.url, @unreachable_url], :fallback)
+
+    assert {:ok, %MintResult{}} = mint(owner, metadata, client)
+    assert StubRpcServer.count(server, "sendTransaction") == 1
+  end
+
+  test "round robin spreads reads over the endpoints", %{owner: owner, metadata: metadata} do
+    first = StubRpcServer.start()
+    second = StubRpcServer.start()
+    client = open([first.url, second.url], :round_robin)
+
+    for _ <- 1..2 do
+      assert {:ok, %MintResult{}} = mint(owner, metadata, client)
+    end
+
+    reads = fn server -> length(StubRpcServer.methods(server)) - StubRpcServer.count(server, "sendTransaction") end
+    assert reads.(first) > 0
+    assert reads.(second) > 0
+  end
+end
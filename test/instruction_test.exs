@@ -0,0 +1,64 @@
+# File: test/instruction_test.exs
+defmodule MplBubblegumEx.InstructionTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.Tree
+  alias MplBubblegumEx.TreeOptions
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.Instruction
+  alias MplBubblegumEx.AccountMeta
+  alias MplBubblegumEx.Solana
+  import TestHelpers
+
+  @bubblegum_program_id "BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY"
+  @system_program_id "11111111111111111111111111111111"
+  @hash "DtjqUcS2m7TQNR1J8rdBMv7UfUsXBedw9191rHjuEJba"
+
+  setup do
+    tree_pubkey = Solana.pubkey_from_keypair(load_test_merkle_keypair())
+    owner_pubkey = test_pubkey()
+
+    metadata = MetadataArgs.new(
+      "Test NFT",
+      "https://example.com/nft-metadata.json",
+      [%Creator{address: owner_pubkey, verified: false, share: 100}]
+    )
+
+    {:ok, %{tree_pubkey: tree_pubkey, owner_pubkey: owner_pubkey, metadata: metadata}}
+  end
+
+  test "create tree returns the create_account and tree config instructions", %{tree_pubkey: tree, owner_pubkey: payer} do
+    {:ok, [create_account_ix, create_tree_ix]} = Tree.create_tree_config_instructions(14, 64, 0, payer, tree)
+
+    assert %Instruction{program_id: @system_program_id} = create_account_ix
+    assert %Instruction{program_id: @bubblegum_program_id} = create_tree_ix
+    assert %AccountMeta{pubkey: ^tree, is_signer: true, is_writable: true} =
+      Enum.at(create_account_ix.accounts, 1)
+  end
+
+  test "create tree takes the tree creator as a pubkey", %{tree_pubkey: tree, owner_pubkey: payer} do
+    options = %TreeOptions{tree_creator: @hash}
+    {:ok, [_create_account_ix, create_tree_ix]} = Tree.create_tree_config_instructions(14, 64, 0, payer, tree, options)
+
+    assert %AccountMeta{pubkey: @hash, is_signer: true} = Enum.at(create_tree_ix.accounts, 3)
+  end
+
+  test "mint instruction marks the payer as a writable signer", %{tree_pubkey: tree, owner_pubkey: owner, metadata: metadata} do
+    {:ok, %Instruction{program_id: @bubblegum_program_id, accounts: accounts, data: data}} =
+      NFT.mint_instruction(tree, owner, owner, metadata)
+
+    assert Enum.any?(accounts, &match?(%AccountMeta{pubkey: ^owner, is_signer: true, is_writable: true}, &1))
+    assert is_binary(data) and byte_size(data) > 8
+  end
+
+  test "transfer instruction only carries the proof nodes below the canopy", %{tree_pubkey: tree, owner_pubkey: owner} do
+    leaf = %{root: @hash, data_hash: @hash, creator_hash: @hash, nonce: 0, index: 0}
+    proof = [tree, tree, tree]
+
+    {:ok, %Instruction{accounts: accounts}} = NFT.transfer_instruction(tree, owner, owner, tree, leaf, proof, 0)
+    {:ok, %Instruction{accounts: trimmed}} = NFT.transfer_instruction(tree, owner, owner, tree, leaf, proof, 2)
+
+    assert length(accounts) - length(trimmed) == 2
+  end
+end
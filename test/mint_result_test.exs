@@ -0,0 +1,122 @@
+# File: test/mint_result_test.exs
+defmodule MplBubblegumEx.MintResultTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.MintResult
+  alias MplBubblegumEx.SendOptions
+  alias MplBubblegumEx.RpcClient
+  alias MplBubblegumEx.RpcOptions
+  import TestHelpers
+
+  setup do
+    owner = test_pubkey()
+
+    metadata = MetadataArgs.new(
+      "Mint Result Test NFT",
+      "https://example.com/nft-metadata.json",
+      [%Creator{address: owner, verified: false, share: 100}]
+    )
+
+    {:ok, %{owner: owner, metadata: metadata}}
+  end
+
+  defp mint(owner, metadata, rpc_client, send_options \\ %SendOptions{}) do
+    NFT.mint(load_test_merkle_keypair(), owner, load_test_keypair(), metadata, nil, send_options, rpc_client)
+  end
+
+  test "mint returns the asset id and leaf from the LeafSchema event", %{owner: owner, metadata: metadata} do
+    server = StubRpcServer.start()
+    leaf = StubRpcServer.leaf()
+
+    assert {:ok, %MintResult{} = result} = mint(owner, metadata, server.url)
+
+    assert result.asset_id == leaf.asset_id
+    assert result.nonce == leaf.nonce
+    assert result.leaf_index == leaf.nonce
+    assert result.data_hash == leaf.data_hash
+    assert result.creator_hash == leaf.creator_hash
+    assert result.commitment == :finalized
+    assert is_binary(result.signature)
+  end
+
+  test "the transaction is fetched again until the node returns it, up to the confirm timeout", %{owner: owner, metadata: metadata} do
+    fetches = :counters.new(1, [])
+
+    server = StubRpcServer.start(fn
+      "getTransaction", params ->
+        :counters.add(fetches, 1, 1)
+        if :counters.get(fetches, 1) < 2, do: nil, else: StubRpcServer.default("getTransaction", params)
+
+      method, params ->
+        StubRpcServer.default(method, params)
+    end)
+
+    {:ok, client} = RpcClient.open(server.url, %RpcOptions{confirm_timeout_ms: 5_000})
+
+    assert {:ok, %MintResult{nonce: 7}} = mint(owner, metadata, client, %SendOptions{commitment: :processed})
+    assert StubRpcServer.count(server, "getTransaction") == 2
+  end
+
+  test "without a confirm timeout the transaction is requested once", %{owner: owner, metadata: metadata} do
+    server = StubRpcServer.start(fn
+      "getTransaction", _params -> nil
+      method, params -> StubRpcServer.default(method, params)
+    end)
+
+    assert {:ok, %MintResult{asset_id: nil}} = mint(owner, metadata, server.url, %SendOptions{commitment: :processed})
+    assert StubRpcServer.count(server, "getTransaction") == 1
+  end
+
+  test "without a LeafSchema event the leaf is located from the tree's mint count", %{owner: owner, metadata: metadata} do
+    server = StubRpcServer.start(no_leaf_event_handler(7, 8))
+    tree = StubRpcServer.base58(binary_part(load_test_merkle_keypair(), 32, 32))
+    {:ok, asset_id} = NFT.get_asset_id(tree, 7)
+
+    assert {:ok, %MintResult{} = result} = mint(owner, metadata, server.url)
+
+    assert result.asset_id == asset_id
+    assert result.nonce == 7
+    assert result.leaf_index == 7
+    assert result.data_hash == nil
+    assert result.creator_hash == nil
+  end
+
+  test "without a LeafSchema event and another mint in between, the leaf is unknown", %{owner: owner, metadata: metadata} do
+    server = StubRpcServer.start(no_leaf_event_handler(7, 9))
+
+    assert {:ok, %MintResult{asset_id: nil, leaf_index: nil, nonce: nil} = result} = mint(owner, metadata, server.url)
+    assert is_binary(result.signature)
+    assert result.commitment == :finalized
+  end
+
+  test "a mint whose transaction is never returned still succeeds", %{owner: owner, metadata: metadata} do
+    server = StubRpcServer.start(fn
+      "getTransaction", _params -> nil
+      method, params -> StubRpcServer.default(method, params)
+    end)
+
+    assert {:ok, %MintResult{asset_id: nil, data_hash: nil} = result} = mint(owner, metadata, server.url)
+    assert is_binary(result.signature)
+  end
+
+  # Transactions without a LeafSchema event, and a tree config that has
+  # `minted_before` NFTs minted on the first read and `minted_after` on the next ones
+  defp no_leaf_event_handler(minted_before, minted_after) do
+    reads = :counters.new(1, [])
+
+    fn
+      "getTransaction", [signature | _] ->
+        StubRpcServer.transaction(signature, [StubRpcServer.change_log_event()])
+
+      "getAccountInfo", _params ->
+        :counters.add(reads, 1, 1)
+        num_minted = if :counters.get(reads, 1) == 1, do: minted_before, else: minted_after
+        StubRpcServer.context(StubRpcServer.tree_config(num_minted))
+
+      method, params ->
+        StubRpcServer.default(method, params)
+    end
+  end
+end
@@ -0,0 +1,86 @@
+# File: test/mint_to_collection_test.exs
+defmodule MplBubblegumEx.MintToCollectionTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.Collection
+  alias MplBubblegumEx.MintResult
+  alias MplBubblegumEx.Instruction
+  import TestHelpers
+
+  # Token Metadata accounts of the collection mint `test_pubkey/0`, derived
+  # with find_metadata_pda and find_master_edition_pda
+  @collection_metadata "8hjXt1Me4qoqufDxUWPQfAZ4mjPyWJCxr1Xo6YqgyS1s"
+  @collection_edition "26E4vFHXQRSnHEHg4nbCFfvQneMfe3gftKfxyrPYh1gv"
+
+  # Account keys of a base64 encoded legacy transaction
+  defp account_keys(tx) do
+    <<signature_count, rest::binary>> = Base.decode64!(tx)
+    <<_signatures::binary-size(signature_count * 64), _header::binary-3, key_count, keys::binary>> = rest
+
+    for <<key::binary-32 <- binary_part(keys, 0, key_count * 32)>>, do: StubRpcServer.base58(key)
+  end
+
+  defp collection_metadata_args(collection_mint) do
+    %{
+      MetadataArgs.new(
+        "Collection Mint Test NFT",
+        "https://example.com/nft-metadata.json",
+        [%Creator{address: collection_mint, verified: false, share: 100}]
+      )
+      | collection: %Collection{verified: false, key: collection_mint}
+    }
+  end
+
+  test "mint_to_collection passes the collection metadata and master edition" do
+    test = self()
+
+    server = StubRpcServer.start(fn
+      "sendTransaction", [tx | _] = params ->
+        send(test, {:sent, tx})
+        StubRpcServer.default("sendTransaction", params)
+
+      method, params ->
+        StubRpcServer.default(method, params)
+    end)
+
+    collection_mint = test_pubkey()
+    metadata = collection_metadata_args(collection_mint)
+
+    assert {:ok, %MintResult{}} =
+      NFT.mint_to_collection(
+        load_test_merkle_keypair(),
+        collection_mint,
+        load_test_keypair(),
+        metadata,
+        collection_mint,
+        load_test_keypair(),
+        server.url
+      )
+
+    assert_received {:sent, tx}
+    keys = account_keys(tx)
+    assert @collection_metadata in keys
+    assert @collection_edition in keys
+  end
+
+  test "the mint to collection instruction has the collection metadata and master edition" do
+    collection_mint = test_pubkey()
+    tree = StubRpcServer.base58(binary_part(load_test_merkle_keypair(), 32, 32))
+
+    assert {:ok, %Instruction{accounts: accounts}} =
+      NFT.mint_to_collection_instruction(
+        tree,
+        collection_mint,
+        collection_mint,
+        collection_metadata_args(collection_mint),
+        collection_mint,
+        collection_mint
+      )
+
+    keys = Enum.map(accounts, & &1.pubkey)
+    assert @collection_metadata in keys
+    assert @collection_edition in keys
+  end
+end
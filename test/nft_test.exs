@@ -5,6 +5,9 @@ defmodule MplBubblegumEx.NFTTest do
   alias MplBubblegumEx.MetadataArgs
   alias MplBubblegumEx.Creator
   alias MplBubblegumEx.Solana
+  alias MplBubblegumEx.SimulationResult
+  alias MplBubblegumEx.MintResult
+  alias MplBubblegumEx.SendOptions
   import TestHelpers
 
   @moduletag :integration
@@ -49,7 +52,7 @@ defmodule MplBubblegumEx.NFTTest do
 
   test "successful NFT minting", %{payer_keypair: payer, tree_keypair: tree_keypair, owner_pubkey: owner, metadata: metadata} do
     # Mint NFT
-    {:ok, tx} = NFT.mint(
+    {:ok, %MintResult{signature: tx, asset_id: asset_id}} = NFT.mint(
       tree_keypair,
       owner,
       payer,
@@ -59,9 +62,31 @@ defmodule MplBubblegumEx.NFTTest do
 
     # Check transaction confirms successfully
     assert {:ok, %{status: "confirmed"}} = Solana.confirm_transaction(tx, @devnet_url)
+    assert is_binary(asset_id)
 
     # Note: Unlike regular NFTs, compressed NFTs don't have their own accounts
     # that we can directly query. The state is stored in the merkle tree.
     # So this is sufficient to verify the transaction succeeded.
   end
+
+  test "mint waits for the requested commitment and reports the slot", %{payer_keypair: payer, tree_keypair: tree_keypair, owner_pubkey: owner, metadata: metadata} do
+    send_options = %SendOptions{commitment: :finalized, preflight_commitment: :confirmed, max_retries: 5}
+
+    assert {:ok, %MintResult{signature: signature, slot: slot, commitment: :finalized}} =
+      NFT.mint(tree_keypair, owner, payer, metadata, nil, send_options, @devnet_url)
+
+    assert is_binary(signature)
+    assert is_integer(slot) and slot > 0
+  end
+
+  test "simulated mint reports compute units without submitting", %{payer_keypair: payer, tree_keypair: tree_keypair, owner_pubkey: owner, metadata: metadata} do
+    tree = Solana.pubkey_from_keypair(tree_keypair)
+    payer_pubkey = Solana.pubkey_from_keypair(payer)
+
+    assert {:ok, %SimulationResult{units_consumed: units, logs: logs}} =
+      NFT.simulate_mint(tree, owner, payer_pubkey, metadata, @devnet_url)
+
+    assert is_integer(units) and units > 0
+    assert is_list(logs)
+  end
 end
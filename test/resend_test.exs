@@ -0,0 +1,89 @@
+# File: test/resend_test.exs
+defmodule MplBubblegumEx.ResendTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.SendOptions
+  alias MplBubblegumEx.MintResult
+  alias MplBubblegumEx.RpcClient
+  alias MplBubblegumEx.RpcOptions
+  import TestHelpers
+
+  setup do
+    owner = test_pubkey()
+
+    metadata = MetadataArgs.new(
+      "Resend Test NFT",
+      "https://example.com/nft-metadata.json",
+      [%Creator{address: owner, verified: false, share: 100}]
+    )
+
+    {:ok, %{owner: owner, metadata: metadata}}
+  end
+
+  defp mint(owner, metadata, send_options, server) do
+    NFT.mint(load_test_merkle_keypair(), owner, load_test_keypair(), metadata, nil, send_options, server.url)
+  end
+
+  # The transaction is never seen and the block height is past the blockhash
+  defp expiring_server do
+    StubRpcServer.start(fn
+      "getSignatureStatuses", _params -> StubRpcServer.context([nil])
+      "getBlockHeight", _params -> 101
+      method, params -> StubRpcServer.default(method, params)
+    end)
+  end
+
+  test "an expired transaction is not re-signed by default", %{owner: owner, metadata: metadata} do
+    server = expiring_server()
+
+    assert {:error, %{kind: :blockhash_not_found}} = mint(owner, metadata, %SendOptions{}, server)
+    assert StubRpcServer.count(server, "sendTransaction") == 1
+  end
+
+  test "an expired transaction is re-signed with a fresh blockhash up to the attempt limit", %{owner: owner, metadata: metadata} do
+    server = expiring_server()
+    send_options = %SendOptions{max_resign_attempts: 2, resign_backoff_ms: 10}
+
+    assert {:error, %{kind: :blockhash_not_found}} = mint(owner, metadata, send_options, server)
+    assert StubRpcServer.count(server, "getLatestBlockhash") == 3
+    assert StubRpcServer.count(server, "sendTransaction") == 3
+  end
+
+  test "a transaction not yet seen is rebroadcast until it lands", %{owner: owner, metadata: metadata} do
+    polls = :counters.new(1, [])
+
+    server = StubRpcServer.start(fn
+      "getSignatureStatuses", _params ->
+        :counters.add(polls, 1, 1)
+        if :counters.get(polls, 1) < 3,
+          do: StubRpcServer.context([nil]),
+          else: StubRpcServer.context([StubRpcServer.finalized_status()])
+
+      method, params ->
+        StubRpcServer.default(method, params)
+    end)
+
+    send_options = %SendOptions{rebroadcast_interval_ms: 0}
+
+    assert {:ok, %MintResult{slot: 1, commitment: :finalized}} = mint(owner, metadata, send_options, server)
+    assert StubRpcServer.count(server, "sendTransaction") == 3
+    assert StubRpcServer.count(server, "getLatestBlockhash") == 1
+  end
+
+  test "a send stops waiting for confirmation at the confirm timeout", %{owner: owner, metadata: metadata} do
+    # The transaction is never seen while its blockhash stays valid
+    server = StubRpcServer.start(fn
+      "getSignatureStatuses", _params -> StubRpcServer.context([nil])
+      method, params -> StubRpcServer.default(method, params)
+    end)
+    {:ok, client} = RpcClient.open(server.url, %RpcOptions{confirm_timeout_ms: 500})
+    send_options = %SendOptions{max_resign_attempts: 2}
+
+    assert {:error, %{kind: :confirmation_timeout}} =
+      NFT.mint(load_test_merkle_keypair(), owner, load_test_keypair(), metadata, nil, send_options, client)
+    assert StubRpcServer.count(server, "sendTransaction") >= 1
+    assert StubRpcServer.count(server, "getLatestBlockhash") == 1
+  end
+end
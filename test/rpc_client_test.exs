@@ -0,0 +1,55 @@
+# File: test/rpc_client_test.exs
+defmodule MplBubblegumEx.RpcClientTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.RpcClient
+  alias MplBubblegumEx.RpcOptions
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.Solana
+  import TestHelpers
+
+  # Nothing listens on port 1, so every RPC call fails before reaching a node
+  @unreachable_url "http://127.0.0.1:1"
+
+  test "opens a client with options" do
+    assert {:ok, client} = RpcClient.open(@unreachable_url, %RpcOptions{
+      commitment: :confirmed,
+      timeout_ms: 1_000,
+      confirm_timeout_ms: 5_000
+    })
+
+    assert is_reference(client)
+  end
+
+  test "rejects a URL that is not http(s)" do
+    assert {:error, :invalid_url} = RpcClient.open("api.devnet.solana.com")
+  end
+
+  test "opens a client with several endpoints" do
+    assert {:ok, client} = RpcClient.open([@unreachable_url, "http://127.0.0.1:2"], %RpcOptions{policy: :round_robin})
+    assert is_reference(client)
+  end
+
+  test "rejects an empty endpoint list or one with an invalid URL" do
+    assert {:error, :invalid_url} = RpcClient.open([])
+    assert {:error, :invalid_url} = RpcClient.open([@unreachable_url, "api.devnet.solana.com"])
+  end
+
+  test "the same client can be reused across calls" do
+    {:ok, client} = RpcClient.open(@unreachable_url, %RpcOptions{timeout_ms: 1_000})
+
+    tree = Solana.pubkey_from_keypair(load_test_merkle_keypair())
+    owner = test_pubkey()
+
+    metadata = MetadataArgs.new(
+      "Test NFT",
+      "https://example.com/nft-metadata.json",
+      [%Creator{address: owner, verified: false, share: 100}]
+    )
+
+    for _ <- 1..2 do
+      assert {:error, %{kind: :network_error}} = NFT.simulate_mint(tree, owner, owner, metadata, client)
+    end
+  end
+end
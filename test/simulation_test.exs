@@ -0,0 +1,73 @@
+# File: test/simulation_test.exs
+defmodule MplBubblegumEx.SimulationTest do
+  use ExUnit.Case
+  alias MplBubblegumEx.NFT
+  alias MplBubblegumEx.MetadataArgs
+  alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.SimulationResult
+  alias MplBubblegumEx.Solana
+  import TestHelpers
+
+  setup do
+    owner = test_pubkey()
+
+    metadata = MetadataArgs.new(
+      "Simulation Test NFT",
+      "https://example.com/nft-metadata.json",
+      [%Creator{address: owner, verified: false, share: 100}]
+    )
+
+    {:ok, %{owner: owner, metadata: metadata}}
+  end
+
+  defp simulate_mint(owner, metadata, server) do
+    tree = Solana.pubkey_from_keypair(load_test_merkle_keypair())
+    NFT.simulate_mint(tree, owner, owner, metadata, server.url)
+  end
+
+  # Server whose simulateTransaction reports `err` with `logs`
+  defp failing_simulation(err, logs) do
+    StubRpcServer.start(fn
+      "simulateTransaction", _params ->
+        StubRpcServer.context(%{"err" => err, "logs" => logs, "accounts" => nil, "unitsConsumed" => 1_000})
+
+      method, params ->
+        StubRpcServer.default(method, params)
+    end)
+  end
+
+  test "a successful simulation has no error", %{owner: owner, metadata: metadata} do
+    server = failing_simulation(nil, ["Program log: ok"])
+
+    assert {:ok, %SimulationResult{error: nil, units_consumed: 1_000, logs: ["Program log: ok"]}} =
+      simulate_mint(owner, metadata, server)
+  end
+
+  test "a failed simulation returns a structured error", %{owner: owner, metadata: metadata} do
+    server = failing_simulation("InsufficientFundsForFee", [])
+
+    assert {:ok, %SimulationResult{error: error}} = simulate_mint(owner, metadata, server)
+    assert %{kind: :insufficient_funds_for_fee, message: message, details: details} = error
+    assert is_binary(message)
+    assert %{transaction_error: transaction_error, instruction_index: nil} = details
+    assert is_binary(transaction_error)
+  end
+
+  test "a custom program error in a simulation is decoded", %{owner: owner, metadata: metadata} do
+    server = failing_simulation(
+      %{"InstructionError" => [0, %{"Custom" => 6001}]},
+      [
+        "Program BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY invoke [1]",
+        "Program BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY failed: custom program error: 0x1771"
+      ]
+    )
+
+    assert {:ok, %SimulationResult{error: error}} = simulate_mint(owner, metadata, server)
+    assert %{kind: :instruction_error, message: "PublicKeyMismatch", details: details} = error
+    assert %{
+      instruction_index: 0,
+      program_id: "BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY",
+      program_error: :public_key_mismatch
+    } = details
+  end
+end
@@ -57,3 +57,208 @@ defmodule TestHelpers do
     end
   end
 end
+
+defmodule StubRpcServer do
+  @moduledoc """
+  Minimal JSON-RPC server on a local port for tests that must not reach a
+  real node. Each call is answered with `handler.(method, params)`, which
+  returns the result or `{:error, error_object}`. Methods the handler does
+  not care about can be passed on to `default/2`.
+  """
+
+  # Blockhash returned by getLatestBlockhash, valid up to block height 100
+  @blockhash :binary.copy(<<1>>, 32)
+  @last_valid_block_height 100
+
+  @noop_program_id "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV"
+
+  @base58_alphabet "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"
+
+  # Leaf logged in the LeafSchema event of every transaction from getTransaction
+  @asset_id :binary.copy(<<2>>, 32)
+  @nonce 7
+  @data_hash :binary.copy(<<3>>, 32)
+  @creator_hash :binary.copy(<<4>>, 32)
+
+  def start(handler \\ &default/2) do
+    {:ok, socket} = :gen_tcp.listen(0, [:binary, packet: :raw, active: false, reuseaddr: true])
+    {:ok, port} = :inet.port(socket)
+    {:ok, calls} = Agent.start_link(fn -> [] end)
+    spawn_link(fn -> accept(socket, handler, calls) end)
+
+    %{url: "http://127.0.0.1:#{port}", calls: calls}
+  end
+
+  @doc """
+  Methods called so far, in order
+  """
+  def methods(%{calls: calls}) do
+    calls |> Agent.get(&Enum.reverse/1) |> Enum.map(fn {method, _params} -> method end)
+  end
+
+  def count(server, method), do: server |> methods() |> Enum.count(&(&1 == method))
+
+  def default("getVersion", _params), do: %{"solana-core" => "1.18.26", "feature-set" => 0}
+  def default("getLatestBlockhash", _params),
+    do: context(%{"blockhash" => base58(@blockhash), "lastValidBlockHeight" => @last_valid_block_height})
+  def default("getBlockHeight", _params), do: 50
+  def default("getMinimumBalanceForRentExemption", _params), do: 1_000_000
+  def default("sendTransaction", [tx | _]), do: signature(tx)
+  def default("getSignatureStatuses", [signatures | _]),
+    do: context(Enum.map(signatures, fn _ -> finalized_status() end))
+  def default("getTransaction", [signature | _]), do: transaction(signature, [change_log_event(), leaf_schema_event()])
+  def default("getAccountInfo", _params), do: context(nil)
+
+  @doc """
+  Leaf of the LeafSchema event in transactions returned by `default/2`
+  """
+  def leaf do
+    %{
+      asset_id: base58(@asset_id),
+      nonce: @nonce,
+      data_hash: base58(@data_hash),
+      creator_hash: base58(@creator_hash)
+    }
+  end
+
+  def context(value), do: %{"context" => %{"slot" => 1}, "value" => value}
+
+  def finalized_status do
+    %{"slot" => 1, "confirmations" => nil, "err" => nil, "status" => %{"Ok" => nil}, "confirmationStatus" => "finalized"}
+  end
+
+  @doc """
+  Confirmed transaction as returned by getTransaction in json encoding, with
+  `events` logged through the noop program as inner instructions
+  """
+  def transaction(signature, events) do
+    instructions = Enum.map(events, fn event ->
+      %{"programIdIndex" => 0, "accounts" => [], "data" => base58(event)}
+    end)
+
+    %{
+      "slot" => 1,
+      "transaction" => %{"signatures" => [signature], "message" => %{"accountKeys" => [@noop_program_id]}},
+      "meta" => %{"err" => nil, "innerInstructions" => [%{"index" => 0, "instructions" => instructions}]}
+    }
+  end
+
+  @doc """
+  Tree config account as returned by getAccountInfo, with `num_minted` NFTs
+  minted in the tree
+  """
+  def tree_config(num_minted) do
+    data = :binary.copy(<<0>>, 8 + 32 + 32) <> <<1_000::little-64, num_minted::little-64, 0, 0>>
+
+    %{
+      "data" => [Base.encode64(data), "base64"],
+      "executable" => false,
+      "lamports" => 1_000_000,
+      "owner" => "BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY",
+      "rentEpoch" => 0,
+      "space" => byte_size(data)
+    }
+  end
+
+  # AccountCompressionEvent::ApplicationData wrapping Bubblegum's LeafSchemaEvent
+  # for `leaf/0`, borsh encoded
+  def leaf_schema_event do
+    owner = :binary.copy(<<5>>, 32)
+    leaf_schema = <<0>> <> @asset_id <> owner <> owner <> <<@nonce::little-64>> <> @data_hash <> @creator_hash
+    application_data = <<1, 0>> <> leaf_schema <> :binary.copy(<<6>>, 32)
+
+    <<1, 0, byte_size(application_data)::little-32>> <> application_data
+  end
+
+  # AccountCompressionEvent::ChangeLog, logged by the account compression program
+  def change_log_event, do: <<0, 0>> <> :binary.copy(<<7>>, 32) <> <<0::little-32, 1::little-64, 7::little-32>>
+
+  # First signature of a base64 encoded transaction
+  def signature(tx) do
+    <<_count, signature::binary-size(64), _rest::binary>> = Base.decode64!(tx)
+    base58(signature)
+  end
+
+  @doc """
+  Base58 encoding of a binary, as used for Solana keys and signatures. Unlike
+  `Base58.encode/1`, which encodes integers, leading zero bytes are kept as
+  leading "1"s.
+  """
+  def base58(binary) do
+    zeros = binary |> :binary.bin_to_list() |> Enum.take_while(&(&1 == 0)) |> length()
+    String.duplicate("1", zeros) <> base58_digits(:binary.decode_unsigned(binary), "")
+  end
+
+  defp base58_digits(0, acc), do: acc
+  defp base58_digits(n, acc), do: base58_digits(div(n, 58), binary_part(@base58_alphabet, rem(n, 58), 1) <> acc)
+
+  defp accept(socket, handler, calls) do
+    {:ok, client} = :gen_tcp.accept(socket)
+    # The connection is served once its socket has been handed over
+    pid = spawn(fn ->
+      receive do
+        :go -> serve(client, handler, calls, "")
+      end
+    end)
+    :ok = :gen_tcp.controlling_process(client, pid)
+    send(pid, :go)
+    accept(socket, handler, calls)
+  end
+
+  defp serve(client, handler, calls, buffer) do
+    case read_request(client, buffer) do
+      {:ok, body, rest} ->
+        payload = body |> Jason.decode!() |> handle(handler, calls) |> Jason.encode!()
+        :gen_tcp.send(client, "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: #{byte_size(payload)}\r\n\r\n" <> payload)
+        serve(client, handler, calls, rest)
+
+      :closed ->
+        :gen_tcp.close(client)
+    end
+  end
+
+  defp read_request(client, buffer) do
+    case :binary.split(buffer, "\r\n\r\n") do
+      [head, rest] -> read_body(client, rest, content_length(head))
+      [_] -> recv(client, buffer, &read_request(client, &1))
+    end
+  end
+
+  defp read_body(_client, buffer, length) when byte_size(buffer) >= length do
+    <<body::binary-size(length), rest::binary>> = buffer
+    {:ok, body, rest}
+  end
+
+  defp read_body(client, buffer, length), do: recv(client, buffer, &read_body(client, &1, length))
+
+  defp recv(client, buffer, continue) do
+    case :gen_tcp.recv(client, 0) do
+      {:ok, data} -> continue.(buffer <> data)
+      {:error, _} -> :closed
+    end
+  end
+
+  defp content_length(head) do
+    head
+    |> String.split("\r\n")
+    |> Enum.find_value(0, fn line ->
+      case String.split(line, ":", parts: 2) do
+        [name, value] ->
+          if String.downcase(name) == "content-length", do: value |> String.trim() |> String.to_integer()
+
+        _ ->
+          nil
+      end
+    end)
+  end
+
+  defp handle(%{"id" => id, "method" => method} = request, handler, calls) do
+    params = Map.get(request, "params", [])
+    Agent.update(calls, &[{method, params} | &1])
+
+    case handler.(method, params) do
+      {:error, error} -> %{"jsonrpc" => "2.0", "id" => id, "error" => error}
+      result -> %{"jsonrpc" => "2.0", "id" => id, "result" => result}
+    end
+  end
+end
@@ -4,15 +4,54 @@ defmodule MplBubblegumEx.TransferTest do
   alias MplBubblegumEx.NFT
   alias MplBubblegumEx.MetadataArgs
   alias MplBubblegumEx.Creator
+  alias MplBubblegumEx.SendResult
+  alias MplBubblegumEx.MintResult
+  alias MplBubblegumEx.Solana
   import TestHelpers
 
   @moduletag :integration
   @devnet_url "https://api.devnet.solana.com"
 
+  # Current root of the tree and proof of its rightmost leaf, read from the
+  # tree account. Minting appends the rightmost leaf, and changing that leaf
+  # leaves its proof as it is, so they are valid for the leaf just minted.
+  defp rightmost_leaf_proof(tree_pubkey) do
+    response = HTTPoison.post!(
+      @devnet_url,
+      Jason.encode!(%{
+        jsonrpc: "2.0",
+        id: 1,
+        method: "getAccountInfo",
+        params: [tree_pubkey, %{"encoding" => "base64", "commitment" => "confirmed"}]
+      }),
+      [{"Content-Type", "application/json"}]
+    )
+    %{"result" => %{"value" => %{"data" => [data, "base64"]}}} = Jason.decode!(response.body)
+
+    # Account type, header version, then the V1 header: max buffer size, max
+    # depth, authority, creation slot, batch initialized flag and padding
+    <<_account_type, _version, max_buffer_size::little-32, max_depth::little-32, _::binary-46,
+      _sequence_number::little-64, active_index::little-64, _buffer_size::little-64,
+      tree::binary>> = Base.decode64!(data)
+
+    # Each change log holds its root, the path of the changed leaf, its index and padding
+    change_log_size = 32 + 32 * max_depth + 8
+    <<change_logs::binary-size(change_log_size * max_buffer_size), rightmost::binary>> = tree
+    <<root::binary-32, _::binary>> = binary_part(change_logs, active_index * change_log_size, change_log_size)
+    <<proof::binary-size(32 * max_depth), _leaf::binary-32, leaf_count::little-32, _::binary>> = rightmost
+
+    %{
+      root: StubRpcServer.base58(root),
+      proof: for(<<node::binary-32 <- proof>>, do: StubRpcServer.base58(node)),
+      index: leaf_count - 1
+    }
+  end
+
   setup do
     # Load test keypairs
     owner_keypair = load_test_keypair()
     tree_keypair = load_test_merkle_keypair()
+    tree_pubkey = Solana.pubkey_from_keypair(tree_keypair)
     owner_pubkey = test_pubkey()
 
     # Create a new recipient for the transfer
@@ -32,7 +71,7 @@ defmodule MplBubblegumEx.TransferTest do
     )
 
     # First mint an NFT to get data_hash, creator_hash, nonce, and index
-    {:ok, mint_result} = NFT.mint(
+    {:ok, %MintResult{signature: mint_signature} = minted} = NFT.mint(
       tree_keypair,
       owner_pubkey,
       owner_keypair,
@@ -40,19 +79,26 @@ defmodule MplBubblegumEx.TransferTest do
       @devnet_url
     )
 
-    IO.puts("Minted NFT with signature: #{mint_result}")
+    IO.puts("Minted NFT with signature: #{mint_signature}")
 
-    # We'd normally extract data_hash, creator_hash, nonce and index from the mint result
-    # For testing, we'll use placeholder values
-    data_hash = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
-    creator_hash = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
-    nonce = 0
-    index = 0
+    data_hash = minted.data_hash
+    creator_hash = minted.creator_hash
+    nonce = minted.nonce
+    index = minted.leaf_index
+    asset_id = minted.asset_id
+
+    # Nothing else minted to the tree in between
+    %{root: root, proof: proof, index: ^index} = rightmost_leaf_proof(tree_pubkey)
 
     {:ok, %{
       owner_keypair: owner_keypair,
+      owner_pubkey: owner_pubkey,
+      asset_id: asset_id,
       tree_keypair: tree_keypair,
+      tree_pubkey: tree_pubkey,
       new_owner_pubkey: new_owner_pubkey,
+      root: root,
+      proof: proof,
       data_hash: data_hash,
       creator_hash: creator_hash,
       nonce: nonce,
@@ -60,22 +106,22 @@ defmodule MplBubblegumEx.TransferTest do
     }}
   end
 
-  test "transfer NFT to new owner", %{owner_keypair: owner, tree_keypair: tree,
-                                      new_owner_pubkey: new_owner,
+  test "transfer NFT to new owner", %{owner_keypair: owner, owner_pubkey: owner_pubkey,
+                                      tree_pubkey: tree, asset_id: asset_id,
+                                      new_owner_pubkey: new_owner, root: root, proof: proof,
                                       data_hash: data_hash, creator_hash: creator_hash,
                                       nonce: nonce, index: index} do
 
     IO.puts("Transferring NFT to #{new_owner}...")
 
     # Execute the transfer
-    {:ok, signature} = NFT.transfer(
+    {:ok, %SendResult{signature: signature}} = NFT.transfer(
       tree,
+      owner_pubkey,
       owner,
       new_owner,
-      data_hash,
-      creator_hash,
-      nonce,
-      index,
+      asset_id,
+      %{root: root, data_hash: data_hash, creator_hash: creator_hash, nonce: nonce, index: index, proof: proof},
       @devnet_url
     )
 
@@ -85,4 +131,91 @@ defmodule MplBubblegumEx.TransferTest do
     assert is_binary(signature)
     assert String.length(signature) > 0
   end
+
+  test "transfer signed by the leaf delegate", %{owner_keypair: owner, owner_pubkey: owner_pubkey,
+                                                 tree_pubkey: tree, asset_id: asset_id,
+                                                 new_owner_pubkey: new_owner, root: root, proof: proof,
+                                                 data_hash: data_hash, creator_hash: creator_hash,
+                                                 nonce: nonce, index: index} do
+    # The delegate signs and pays for the transfer, so it needs a real keypair with some SOL
+    {public_key, secret_key} = :crypto.generate_key(:eddsa, :ed25519)
+    delegate_keypair = secret_key <> public_key
+    delegate_pubkey = StubRpcServer.base58(public_key)
+
+    {:ok, airdrop} = Solana.request_airdrop(delegate_pubkey, 0.01, @devnet_url)
+    {:ok, _} = Solana.wait_for_confirmation(airdrop, @devnet_url)
+
+    leaf = %{root: root, data_hash: data_hash, creator_hash: creator_hash, nonce: nonce, index: index}
+    {:ok, %SendResult{}} = NFT.delegate(tree, owner, delegate_pubkey, leaf, proof, @devnet_url)
+
+    # Setting the delegate changed the leaf, and with it the root
+    %{root: root, proof: proof, index: ^index} = rightmost_leaf_proof(tree)
+
+    assert {:ok, %SendResult{signature: signature}} = NFT.transfer(
+      tree,
+      owner_pubkey,
+      delegate_keypair,
+      new_owner,
+      asset_id,
+      %{
+        leaf_delegate: delegate_pubkey,
+        root: root,
+        data_hash: data_hash,
+        creator_hash: creator_hash,
+        nonce: nonce,
+        index: index,
+        proof: proof
+      },
+      @devnet_url
+    )
+
+    assert is_binary(signature)
+  end
+
+  test "transfer with a stale proof returns the decoded program error", %{owner_keypair: owner, owner_pubkey: owner_pubkey,
+                                                                        tree_keypair: tree, asset_id: asset_id,
+                                                                        new_owner_pubkey: new_owner,
+                                                                        nonce: nonce, index: index} do
+    # Any 32-byte value works as a root and hashes that do not match the leaf
+    stale = "5bvDGqzX4QdArhG5fvEFArKYeyXZRddxGP4XECGnrNqA"
+
+    assert {:error, %{kind: :instruction_error, message: message, details: details}} = NFT.transfer(
+      tree,
+      owner_pubkey,
+      owner,
+      new_owner,
+      asset_id,
+      %{root: stale, data_hash: stale, creator_hash: stale, nonce: nonce, index: index},
+      @devnet_url
+    )
+
+    # The merkle tree error is raised by the account compression program. The
+    # stale root is not in the change log, so the whole log is replayed, which
+    # reaches the append of the minted leaf and finds its contents changed.
+    assert %{program_id: "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK", program_error: :leaf_contents_modified} = details
+    refute message =~ "custom program error"
+  end
+
+  test "transfer without every leaf field is rejected", %{owner_keypair: owner, owner_pubkey: owner_pubkey,
+                                                          tree_keypair: tree, asset_id: asset_id,
+                                                          new_owner_pubkey: new_owner, data_hash: data_hash} do
+    assert {:error, :missing_leaf_fields} =
+      NFT.transfer(tree, owner_pubkey, owner, new_owner, asset_id, %{root: data_hash}, @devnet_url)
+  end
+
+  test "transfer with an asset id of another nonce is rejected", %{owner_keypair: owner, owner_pubkey: owner_pubkey,
+                                                                   tree_keypair: tree, asset_id: asset_id,
+                                                                   new_owner_pubkey: new_owner,
+                                                                   data_hash: data_hash, creator_hash: creator_hash,
+                                                                   nonce: nonce, index: index} do
+    assert {:error, :asset_id_mismatch} = NFT.transfer(
+      tree,
+      owner_pubkey,
+      owner,
+      new_owner,
+      asset_id,
+      %{root: data_hash, data_hash: data_hash, creator_hash: creator_hash, nonce: nonce + 1, index: index},
+      @devnet_url
+    )
+  end
 end
@@ -2,6 +2,7 @@ defmodule MplBubblegumEx.TreeIntegrationTest do
   use ExUnit.Case
   alias MplBubblegumEx.Tree
   alias MplBubblegumEx.Solana
+  alias MplBubblegumEx.SendResult
   import TestHelpers
 
   @moduletag :integration
@@ -27,7 +28,7 @@ defmodule MplBubblegumEx.TreeIntegrationTest do
   end
 
   test "successful tree creation", %{payer_keypair: payer, tree_keypair: tree_keypair} do
-    {:ok, tx} = Tree.create_tree_config(
+    {:ok, %SendResult{signature: tx}} = Tree.create_tree_config(
       3,
       8,
       payer,